@@ -0,0 +1,11 @@
+extern crate mockers_codegen;
+
+use std::env;
+use std::path::Path;
+
+fn main() {
+    let out_dir = env::var_os("OUT_DIR").unwrap();
+    let dst = Path::new(&out_dir).join("mocks.rs");
+
+    mockers_codegen::generate_mocks("src", &dst).unwrap();
+}
@@ -0,0 +1,34 @@
+// `#[mockable]` marks traits for build script only. Stable compiler
+// rejects unknown attributes, so this example requires nightly Rust.
+// Nightly accepts them, but reports as unused.
+#![feature(custom_attribute)]
+#![allow(unused_attributes)]
+
+#[cfg(test)] extern crate mockers;
+#[cfg(test)] mod tests;
+#[cfg(test)]
+mod mocks {
+    include!(concat!(env!("OUT_DIR"), "/mocks.rs"));
+}
+
+pub mod sensors;
+
+use sensors::Thermometer;
+
+/// Temperature in degrees Celsius.
+pub type Temperature = i16;
+
+#[mockable]
+pub trait AirConditioner {
+    fn make_hotter(&mut self, by: Temperature);
+    fn make_cooler(&mut self, by: Temperature);
+}
+
+pub fn set_temperature_20(cond: &mut AirConditioner, thermometer: &Thermometer) {
+    let t = thermometer.temperature();
+    if t < 20 {
+        cond.make_hotter(20 - t);
+    } else {
+        cond.make_cooler(t - 20);
+    }
+}
@@ -0,0 +1,4 @@
+#[mockable]
+pub trait Thermometer {
+    fn temperature(&self) -> i16;
+}
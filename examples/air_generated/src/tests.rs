@@ -0,0 +1,16 @@
+use super::set_temperature_20;
+use mocks::AirConditionerMock;
+use mocks::sensors::ThermometerMock;
+use mockers::Scenario;
+
+#[test]
+fn test_set_temperature_20() {
+    let mut scenario = Scenario::new();
+    let mut cond = scenario.create_mock::<AirConditionerMock>();
+    let thermometer = scenario.create_mock::<ThermometerMock>();
+
+    scenario.expect(thermometer.temperature_call().and_return(16));
+    scenario.expect(cond.make_hotter_call(4).and_return(()));
+
+    set_temperature_20(&mut cond, &thermometer);
+}
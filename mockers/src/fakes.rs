@@ -0,0 +1,323 @@
+//! Fakes for code working with byte streams.
+//!
+//! Mocking `read` and `write` call by call is brittle, because the way
+//! data is split into calls depends on buffer sizes. Fakes accept any
+//! calls and are checked when scenario is verified instead:
+//!
+//! * `ScriptedReader` serves scripted sequence of chunks, errors and
+//!   `WouldBlock`s, all of them must be read by checkpoint,
+//! * `RecordingWriter` accumulates written data, which must satisfy
+//!   matchers given with `expect_output` by checkpoint.
+//!
+//! Fakes are created like mocks, so they are named in error messages:
+//!
+//! ```ignore
+//! let reader = scenario.create_mock::<ScriptedReader>();
+//! reader.chunk(b"HELLO").would_block().chunk(b"\n");
+//!
+//! let writer = scenario.create_mock::<RecordingWriter>();
+//! writer.expect_text(eq("WORLD\n".to_owned()));
+//!
+//! serve(reader.clone(), writer.clone());
+//! ```
+//!
+//! Clones of fake share its script or output, so they may be used after
+//! fake is moved into tested code.
+
+use std::cell::{Cell, RefCell};
+use std::cmp;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::rc::Rc;
+
+use super::{Mock, ScenarioInternals, Expectation, CallMatch, MatchArg, Call, TypeParams};
+
+/// Call matcher of fake expectations, they aren't satisfied by calls,
+/// but checked on verification only.
+struct NoCallMatch {
+    mock_id: usize,
+    method_name: &'static str,
+}
+impl CallMatch for NoCallMatch {
+    fn matches_args(&self, _call: &Call) -> bool { false }
+    fn matches_target(&self, _call: &Call) -> bool { false }
+    fn matches_method(&self, _call: &Call) -> bool { false }
+    fn validate(&self, _call: &Call) -> Vec<Result<(), String>> { Vec::new() }
+    fn get_mock_id(&self) -> usize { self.mock_id }
+    fn get_mock_type_id(&self) -> usize { 0 }
+    fn get_method_name(&self) -> &'static str { self.method_name }
+    fn get_type_params(&self) -> TypeParams { TypeParams::of::<()>() }
+    fn describe(&self) -> String { format!("{}()", self.method_name) }
+}
+
+enum Step {
+    Data(Vec<u8>),
+    Error(io::ErrorKind, String),
+    WouldBlock,
+}
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Step::Data(ref data) => write!(f, "chunk {:?}", String::from_utf8_lossy(data)),
+            Step::Error(kind, ref message) => write!(f, "error {:?} ({})", kind, message),
+            Step::WouldBlock => f.write_str("WouldBlock"),
+        }
+    }
+}
+
+/// Reader serving scripted sequence of chunks, errors and `WouldBlock`s.
+///
+/// Each `read` call returns at most one chunk, so code is tested against
+/// given data split. Chunk which doesn't fit into buffer is returned by
+/// several calls. When script is over, `read` returns `Ok(0)`.
+///
+/// Expectation that script is consumed is added to scenario with first
+/// step, and again with first step after checkpoint.
+pub struct ScriptedReader {
+    scenario: Rc<RefCell<ScenarioInternals>>,
+    mock_id: usize,
+    script: Rc<RefCell<VecDeque<Step>>>,
+    /// Whether `ScriptConsumed` expectation is in scenario.
+    expected: Rc<Cell<bool>>,
+}
+
+impl ScriptedReader {
+    /// Adds chunk of data to script.
+    ///
+    /// Chunk must not be empty: reading it would return `Ok(0)`, which
+    /// tested code takes for end of stream.
+    pub fn chunk<D: AsRef<[u8]>>(&self, data: D) -> &Self {
+        let data = data.as_ref();
+        if data.is_empty() {
+            panic!("empty chunk is added to script of {:?}, reading it would signal end of stream, \
+                    so the rest of script would never be read", self);
+        }
+        self.push_step(Step::Data(data.to_vec()))
+    }
+
+    /// Adds error to script.
+    pub fn error(&self, kind: io::ErrorKind, message: &str) -> &Self {
+        self.push_step(Step::Error(kind, message.to_owned()))
+    }
+
+    /// Adds `WouldBlock` error to script, like non-blocking reader
+    /// which has no data yet.
+    pub fn would_block(&self) -> &Self {
+        self.push_step(Step::WouldBlock)
+    }
+
+    fn push_step(&self, step: Step) -> &Self {
+        // Checkpoint removes expectation, so it is added again.
+        if !self.expected.get() {
+            self.scenario.borrow_mut().expectations.push(Box::new(ScriptConsumed {
+                call_match: NoCallMatch { mock_id: self.mock_id, method_name: "read" },
+                script: self.script.clone(),
+                expected: self.expected.clone(),
+            }));
+            self.expected.set(true);
+        }
+        self.script.borrow_mut().push_back(step);
+        self
+    }
+}
+
+impl Mock for ScriptedReader {
+    fn new(id: usize, scenario_int: Rc<RefCell<ScenarioInternals>>) -> Self {
+        ScriptedReader {
+            scenario: scenario_int,
+            mock_id: id,
+            script: Rc::new(RefCell::new(VecDeque::new())),
+            expected: Rc::new(Cell::new(false)),
+        }
+    }
+
+    fn mocked_class_name() -> &'static str {
+        "ScriptedReader"
+    }
+}
+
+impl Read for ScriptedReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut script = self.script.borrow_mut();
+        match script.pop_front() {
+            None => Ok(0),
+            Some(Step::Data(mut data)) => {
+                let len = cmp::min(buf.len(), data.len());
+                buf[..len].copy_from_slice(&data[..len]);
+                if len < data.len() {
+                    script.push_front(Step::Data(data.split_off(len)));
+                }
+                Ok(len)
+            },
+            Some(Step::Error(kind, message)) => Err(io::Error::new(kind, message)),
+            Some(Step::WouldBlock) => Err(io::Error::new(io::ErrorKind::WouldBlock, "operation would block")),
+        }
+    }
+}
+
+impl Clone for ScriptedReader {
+    fn clone(&self) -> Self {
+        ScriptedReader {
+            scenario: self.scenario.clone(),
+            mock_id: self.mock_id,
+            script: self.script.clone(),
+            expected: self.expected.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for ScriptedReader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Fake may be printed by action while scenario is borrowed.
+        match self.scenario.try_borrow() {
+            Ok(scenario) => f.write_str(scenario.get_mock_name(self.mock_id)),
+            Err(_) => write!(f, "#{}", self.mock_id),
+        }
+    }
+}
+
+/// Expects whole script of reader to be read.
+struct ScriptConsumed {
+    call_match: NoCallMatch,
+    script: Rc<RefCell<VecDeque<Step>>>,
+    expected: Rc<Cell<bool>>,
+}
+impl Drop for ScriptConsumed {
+    fn drop(&mut self) {
+        self.expected.set(false);
+    }
+}
+impl Expectation for ScriptConsumed {
+    fn call_match(&self) -> &CallMatch { &self.call_match }
+    fn is_satisfied(&self) -> bool { self.script.borrow().is_empty() }
+    fn satisfy(&mut self, _call: Call, _mock_name: &str) -> *mut u8 {
+        unreachable!("fake expectations are never matched with calls")
+    }
+    fn describe(&self) -> String {
+        "read() must consume whole script".to_owned()
+    }
+    fn mismatches(&self) -> Vec<(String, String)> {
+        let script = self.script.borrow();
+        match script.front() {
+            Some(step) => vec![("script".to_owned(),
+                                format!("{} steps are left, next is {}", script.len(), step))],
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Writer accumulating all written data.
+///
+/// Output is matched with mockers matchers when scenario is verified,
+/// it isn't cleared by checkpoints.
+pub struct RecordingWriter {
+    scenario: Rc<RefCell<ScenarioInternals>>,
+    mock_id: usize,
+    output: Rc<RefCell<Vec<u8>>>,
+}
+
+impl RecordingWriter {
+    /// Returns all data written so far.
+    pub fn output(&self) -> Vec<u8> {
+        self.output.borrow().clone()
+    }
+
+    /// Expects output to match given matcher by next checkpoint.
+    pub fn expect_output<M: MatchArg<Vec<u8>> + 'static>(&self, matcher: M) {
+        self.expect_converted(matcher, output_bytes);
+    }
+
+    /// Expects output, converted to string, to match given matcher
+    /// by next checkpoint. Invalid UTF-8 sequences are replaced.
+    pub fn expect_text<M: MatchArg<String> + 'static>(&self, matcher: M) {
+        self.expect_converted(matcher, output_text);
+    }
+
+    fn expect_converted<T: 'static, M: MatchArg<T> + 'static>(&self, matcher: M, convert: fn(&[u8]) -> T) {
+        self.scenario.borrow_mut().expectations.push(Box::new(OutputMatches {
+            call_match: NoCallMatch { mock_id: self.mock_id, method_name: "write" },
+            output: self.output.clone(),
+            convert: convert,
+            matcher: Box::new(matcher),
+        }));
+    }
+}
+
+fn output_bytes(output: &[u8]) -> Vec<u8> { output.to_vec() }
+fn output_text(output: &[u8]) -> String { String::from_utf8_lossy(output).into_owned() }
+
+impl Mock for RecordingWriter {
+    fn new(id: usize, scenario_int: Rc<RefCell<ScenarioInternals>>) -> Self {
+        RecordingWriter {
+            scenario: scenario_int,
+            mock_id: id,
+            output: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    fn mocked_class_name() -> &'static str {
+        "RecordingWriter"
+    }
+}
+
+impl Write for RecordingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Clone for RecordingWriter {
+    fn clone(&self) -> Self {
+        RecordingWriter {
+            scenario: self.scenario.clone(),
+            mock_id: self.mock_id,
+            output: self.output.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for RecordingWriter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Fake may be printed by action while scenario is borrowed.
+        match self.scenario.try_borrow() {
+            Ok(scenario) => f.write_str(scenario.get_mock_name(self.mock_id)),
+            Err(_) => write!(f, "#{}", self.mock_id),
+        }
+    }
+}
+
+/// Expects output of writer to match matcher.
+struct OutputMatches<T> {
+    call_match: NoCallMatch,
+    output: Rc<RefCell<Vec<u8>>>,
+    convert: fn(&[u8]) -> T,
+    matcher: Box<MatchArg<T>>,
+}
+impl<T> OutputMatches<T> {
+    fn check(&self) -> Result<(), String> {
+        let output = (self.convert)(&self.output.borrow());
+        self.matcher.matches(&output)
+    }
+}
+impl<T> Expectation for OutputMatches<T> {
+    fn call_match(&self) -> &CallMatch { &self.call_match }
+    fn is_satisfied(&self) -> bool { self.check().is_ok() }
+    fn satisfy(&mut self, _call: Call, _mock_name: &str) -> *mut u8 {
+        unreachable!("fake expectations are never matched with calls")
+    }
+    fn describe(&self) -> String {
+        format!("output({})", self.matcher.describe())
+    }
+    fn mismatches(&self) -> Vec<(String, String)> {
+        match self.check() {
+            Ok(()) => Vec::new(),
+            Err(err) => vec![("output".to_owned(), err)],
+        }
+    }
+}
@@ -1,12 +1,14 @@
 #![cfg_attr(feature="nightly", feature(fnbox))]
 #![cfg_attr(feature="nightly", feature(collections, collections_range, collections_bound))]
+#![cfg_attr(feature="nightly", feature(core_intrinsics))]
 // nightly(box_patterns) #![feature(box_patterns)]
 
 #[cfg(feature="nightly")]
 extern crate collections;
 
+use std::any::TypeId;
 use std::marker::PhantomData;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 
@@ -14,21 +16,18 @@ use std::fmt::Write;
 
 mod box_fn;
 pub mod matchers;
+#[cfg(feature="std_mocks")]
+pub mod std_mocks;
+pub mod fakes;
 
 type Action0<T> = box_fn::BoxFn0<T>;
 type ActionClone0<T> = Box<FnMut() -> T>;
 
-type Action1<Arg0, T> = box_fn::BoxFn1<Arg0, T>;
-type ActionClone1<Arg0, T> = Box<FnMut(Arg0) -> T>;
-
-type Action2<Arg0, Arg1, T> = box_fn::BoxFn2<Arg0, Arg1, T>;
-type ActionClone2<Arg0, Arg1, T> = Box<FnMut(Arg0, Arg1) -> T>;
-
-type Action3<Arg0, Arg1, Arg2, T> = box_fn::BoxFn3<Arg0, Arg1, Arg2, T>;
-type ActionClone3<Arg0, Arg1, Arg2, T> = Box<FnMut(Arg0, Arg1, Arg2) -> T>;
-
-type Action4<Arg0, Arg1, Arg2, Arg3, T> = box_fn::BoxFn4<Arg0, Arg1, Arg2, Arg3, T>;
-type ActionClone4<Arg0, Arg1, Arg2, Arg3, T> = Box<FnMut(Arg0, Arg1, Arg2, Arg3) -> T>;
+/// Action of method with arguments, it takes call and extracts
+/// arguments itself, because types of borrowed arguments depend
+/// on call lifetime.
+type ArgsAction<T> = box_fn::BoxFn1<Call, T>;
+type ArgsActionClone<T> = Box<FnMut(Call) -> T>;
 
 pub trait CallMatch {
     fn matches_args(&self, call: &Call) -> bool;
@@ -37,16 +36,19 @@ pub trait CallMatch {
     }
     fn matches_target(&self, call: &Call) -> bool {
         self.get_mock_id() == call.mock_id &&
-        self.get_method_name() == call.method_name
+        self.get_method_name() == call.method_name &&
+        self.get_type_params() == call.type_params
     }
     fn matches_method(&self, call: &Call) -> bool {
         self.get_mock_type_id() == call.mock_type_id &&
-        self.get_method_name() == call.method_name
+        self.get_method_name() == call.method_name &&
+        self.get_type_params() == call.type_params
     }
     fn validate(&self, call: &Call) -> Vec<Result<(), String>>;
     fn get_mock_id(&self) -> usize;
     fn get_mock_type_id(&self) -> usize;
     fn get_method_name(&self) -> &'static str;
+    fn get_type_params(&self) -> TypeParams;
     fn describe(&self) -> String;
 }
 
@@ -55,6 +57,10 @@ pub trait Expectation {
     fn is_satisfied(&self) -> bool;
     fn satisfy(&mut self, call: Call, mock_name: &str) -> *mut u8;
     fn describe(&self) -> String;
+    /// Explains why expectation isn't satisfied, pairs of subject and
+    /// problem are printed under expectation description when scenario
+    /// is verified.
+    fn mismatches(&self) -> Vec<(String, String)> { Vec::new() }
 }
 
 pub struct ExpectationNever<CM: CallMatch> {
@@ -75,20 +81,140 @@ impl<CM: CallMatch> Expectation for ExpectationNever<CM> {
     }
 }
 
+/// Formats argument matcher description, prefixing it with argument
+/// name if it is known.
+fn describe_arg(arg_names: &[&str], index: usize, matcher: &str) -> String {
+    match arg_names.get(index) {
+        Some(name) if !name.is_empty() => format!("{}: {}", name, matcher),
+        _ => matcher.to_owned(),
+    }
+}
+
+/// Validates call with predicate given to `*_call_with` method, if any.
+fn validate_predicate(predicate: &Option<Box<Fn(&Call) -> bool>>, call: &Call) -> Option<Result<(), String>> {
+    predicate.as_ref().map(|predicate| {
+        if predicate(call) { Ok(()) } else { Err("arguments don't satisfy predicate".to_owned()) }
+    })
+}
+
+/// Describes how argument is passed to mocked method.
+///
+/// Argument matchers always get argument value, but actions and
+/// predicates get argument as it is passed, so borrowed arguments
+/// are given to them for any lifetime `'a` and can't be kept after call.
+pub trait ArgKind<'a, T: ?Sized + 'static> {
+    /// Type of argument passed with lifetime `'a`.
+    type Arg;
+    /// Returns value to be matched by argument matcher.
+    fn matched<'b>(arg: &'b Self::Arg) -> &'b T where 'a: 'b;
+}
+
+/// Argument passed by value, `T`.
+pub struct ByValue;
+impl<'a, T: 'static> ArgKind<'a, T> for ByValue {
+    type Arg = T;
+    fn matched<'b>(arg: &'b T) -> &'b T where 'a: 'b { arg }
+}
+
+/// Argument passed by shared reference, `&T`.
+pub struct ByRef;
+impl<'a, T: ?Sized + 'static> ArgKind<'a, T> for ByRef {
+    type Arg = &'a T;
+    fn matched<'b>(arg: &'b &'a T) -> &'b T where 'a: 'b { *arg }
+}
+
+/// Argument passed by mutable reference, `&mut T`.
+pub struct ByMutRef;
+impl<'a, T: ?Sized + 'static> ArgKind<'a, T> for ByMutRef {
+    type Arg = &'a mut T;
+    fn matched<'b>(arg: &'b &'a mut T) -> &'b T where 'a: 'b { &**arg }
+}
+
+/// Implements methods taking actions and predicates of `CallMatchN` and
+/// `ExpectationBuilderN` for each combination of argument kinds.
+///
+/// Closure bounds are written with argument types of concrete kinds,
+/// because closure signature can't be inferred from bound using
+/// `ArgKind::Arg` projection under `for<'a>`.
+macro_rules! kind_impls {
+    (@kinds $call_match:ident, $expectation:ident, $reaction:ident, $builder:ident;
+     [$($done:tt)*]; $Arg:ident $arg:ident $(, $rest_arg:ident $rest:ident)*) => {
+        kind_impls!(@kinds $call_match, $expectation, $reaction, $builder;
+                    [$($done)* ($Arg $arg ByValue ($Arg) [$Arg: Sized,])]; $($rest_arg $rest),*);
+        kind_impls!(@kinds $call_match, $expectation, $reaction, $builder;
+                    [$($done)* ($Arg $arg ByRef (&'a $Arg) [])]; $($rest_arg $rest),*);
+        kind_impls!(@kinds $call_match, $expectation, $reaction, $builder;
+                    [$($done)* ($Arg $arg ByMutRef (&'a mut $Arg) [])]; $($rest_arg $rest),*);
+    };
+
+    (@kinds $call_match:ident, $expectation:ident, $reaction:ident, $builder:ident;
+     [$(($Arg:ident $arg:ident $Kind:ident ($ty:ty) [$($bound:tt)*]))*];) => {
+        impl<$($Arg: ?Sized + 'static,)* Res> $call_match<$($Arg,)* Res, $($Kind),*>
+            where Res: 'static, $($($bound)*)* {
+            /// Adds predicate checking all arguments together, it is used
+            /// by `*_call_with` methods of mocks.
+            pub fn with_predicate<P>(mut self, predicate: P) -> Self
+                    where P: for<'a> Fn(&($($ty,)*)) -> bool + 'static {
+                self.predicate = Some(Box::new(move |call: &Call| predicate(&*Self::get_args_ref(call))));
+                self
+            }
+
+            pub fn and_call<F>(self, func: F) -> $expectation<$($Arg,)* Res, $($Kind),*>
+                    where F: for<'a> FnOnce($($ty),*) -> Res + 'static {
+                $expectation { call_match: self, action: Some(ArgsAction::new(move |call| {
+                    // nightly: let box ($($arg,)*) = Self::get_args(call);
+                    let ($($arg,)*) = *Self::get_args(call);
+                    func($($arg),*)
+                })) }
+            }
+        }
+        impl<$($Arg: ?Sized + 'static,)* Res> $call_match<$($Arg,)* Res, $($Kind),*>
+            where Res: Clone + 'static, $($($bound)*)* {
+            pub fn and_call_clone<F>(self, mut func: F) -> $reaction<$($Arg,)* Res, $($Kind),*>
+                    where F: for<'a> FnMut($($ty),*) -> Res + 'static {
+                $reaction { call_match: self, action: Box::new(move |call| {
+                    // nightly: let box ($($arg,)*) = Self::get_args(call);
+                    let ($($arg,)*) = *Self::get_args(call);
+                    func($($arg),*)
+                }) }
+            }
+        }
+        impl<$($Arg: ?Sized + 'static,)* Res> $builder<$($Arg,)* Res, $($Kind),*>
+            where Res: 'static, $($($bound)*)* {
+            pub fn returning<F>(self, mut func: F) -> Self
+                    where F: for<'a> FnMut($($ty),*) -> Res + 'static {
+                self.slot.borrow_mut().action = Some(Box::new(move |call| {
+                    // nightly: let box ($($arg,)*) = $call_match::<$($Arg,)* Res, $($Kind),*>::get_args(call);
+                    let ($($arg,)*) = *$call_match::<$($Arg,)* Res, $($Kind),*>::get_args(call);
+                    func($($arg),*)
+                }));
+                self
+            }
+        }
+    };
+
+    ($call_match:ident, $expectation:ident, $reaction:ident, $builder:ident; $($Arg:ident $arg:ident),*) => {
+        kind_impls!(@kinds $call_match, $expectation, $reaction, $builder; []; $($Arg $arg),*);
+    };
+}
+
 #[must_use]
 pub struct CallMatch0<Res> {
     mock_id: usize,
     mock_type_id: usize,
     method_name: &'static str,
+    type_params: TypeParams,
 
     _phantom: PhantomData<Res>,
 }
 impl<Res> CallMatch0<Res> {
-    pub fn new(mock_id: usize, mock_type_id: usize, method_name: &'static str) -> Self {
+    pub fn new(mock_id: usize, mock_type_id: usize, method_name: &'static str,
+               type_params: TypeParams, _arg_names: &'static [&'static str]) -> Self {
         CallMatch0 {
             mock_id: mock_id,
             mock_type_id: mock_type_id,
             method_name: method_name,
+            type_params: type_params,
             _phantom: PhantomData
         }
     }
@@ -100,7 +226,8 @@ impl<Res> CallMatch0<Res> {
 impl<Res> CallMatch for CallMatch0<Res> {
     fn matches_args(&self, call: &Call) -> bool {
         assert!(call.mock_type_id == self.mock_type_id &&
-                call.method_name == self.method_name);
+                call.method_name == self.method_name &&
+                call.type_params == self.type_params);
         true
     }
     fn validate(&self, _call: &Call) -> Vec<Result<(), String>> {
@@ -109,6 +236,7 @@ impl<Res> CallMatch for CallMatch0<Res> {
     fn get_mock_id(&self) -> usize { self.mock_id }
     fn get_mock_type_id(&self) -> usize { self.mock_type_id }
     fn get_method_name(&self) -> &'static str { self.method_name }
+    fn get_type_params(&self) -> TypeParams { self.type_params }
     fn describe(&self) -> String {
         format!("{}()", self.method_name)
     }
@@ -137,7 +265,7 @@ impl<Res> ExpectationTimes0<Res> {
         ExpectationTimes0 { call_match: call_match, action: action, number: number, count: 0 }
     }
 }
-impl<Res: Clone> Expectation for ExpectationTimes0<Res> {
+impl<Res> Expectation for ExpectationTimes0<Res> {
     fn call_match(&self) -> &CallMatch {
         &self.call_match
     }
@@ -175,8 +303,7 @@ impl<Res> Expectation for Expectation0<Res> {
     fn satisfy(&mut self, call: Call, mock_name: &str) -> *mut u8 {
         match self.action.take() {
             Some(action) => {
-                // nightly: let box () = CallMatch0::<Res>::get_args(call);
-                let () = *CallMatch0::<Res>::get_args(call);
+                CallMatch0::<Res>::get_args(call);
                 let result = action.call();
                 Box::into_raw(Box::new(result)) as *mut u8
             },
@@ -223,79 +350,210 @@ impl<Res: Default + 'static> CallMatch0<Res> {
     }
 }
 
+/// Result and number of calls of expectation added by `expect_*` method,
+/// shared between expectation and its builder.
+struct BuilderSlot<Res> {
+    action: Option<ArgsActionClone<Res>>,
+    number: usize,
+}
+impl<Res: 'static> BuilderSlot<Res> {
+    /// Adds expectation with given call matcher to scenario and returns
+    /// slot which builder fills.
+    fn register<C: CallMatch + 'static>(scenario: &RefCell<ScenarioInternals>, call_match: C)
+            -> Rc<RefCell<Self>> {
+        let slot = Rc::new(RefCell::new(BuilderSlot { action: None, number: 1 }));
+        let expectation = BuilderExpectation { call_match: call_match, slot: slot.clone(), count: 0 };
+        scenario.borrow_mut().expectations.push(Box::new(expectation));
+        slot
+    }
+}
+
+/// Expectation added by `expect_*` method, it is configured by builder
+/// after it is added to scenario.
+struct BuilderExpectation<C: CallMatch, Res> {
+    call_match: C,
+    slot: Rc<RefCell<BuilderSlot<Res>>>,
+    count: usize,
+}
+impl<C: CallMatch, Res> Expectation for BuilderExpectation<C, Res> {
+    fn call_match(&self) -> &CallMatch {
+        &self.call_match
+    }
+    fn is_satisfied(&self) -> bool {
+        let slot = self.slot.borrow();
+        self.count == slot.number && (slot.action.is_some() || slot.number == 0)
+    }
+    fn satisfy(&mut self, call: Call, mock_name: &str) -> *mut u8 {
+        let number = self.slot.borrow().number;
+        if self.count == number {
+            panic!("{}.{} was already called {} times of {} expected, extra call is unexpected",
+                   mock_name, self.call_match.get_method_name(), self.count, number);
+        }
+        // Action is taken out of slot while it is called, so it may use builder.
+        let mut action = match self.slot.borrow_mut().action.take() {
+            Some(action) => action,
+            None => panic!("result of `{}.{}` isn't given, use `returning`",
+                           mock_name, self.call_match.describe()),
+        };
+        self.count += 1;
+        let result = action(call);
+        self.slot.borrow_mut().action = Some(action);
+        Box::into_raw(Box::new(result)) as *mut u8
+    }
+    fn describe(&self) -> String {
+        format!("{} must be called {} times, called {} times",
+                self.call_match.describe(), self.slot.borrow().number, self.count)
+    }
+    fn mismatches(&self) -> Vec<(String, String)> {
+        if self.slot.borrow().action.is_none() {
+            vec![("result".to_owned(), "isn't given, use `returning`".to_owned())]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Argument matcher which may be replaced by builder after expectation
+/// is added to scenario.
+struct SharedMatchArg<T: ?Sized>(Rc<RefCell<Box<MatchArg<T>>>>);
+impl<T: ?Sized> MatchArg<T> for SharedMatchArg<T> {
+    fn matches(&self, arg: &T) -> Result<(), String> {
+        self.0.borrow().matches(arg)
+    }
+    fn describe(&self) -> String {
+        self.0.borrow().describe()
+    }
+}
+
+/// Replaces matcher with shared one and returns slot for replacing it.
+fn share_matcher<T: ?Sized + 'static>(matcher: &mut Box<MatchArg<T>>) -> Rc<RefCell<Box<MatchArg<T>>>> {
+    let shared = Rc::new(RefCell::new(std::mem::replace(matcher, Box::new(matchers::ANY))));
+    *matcher = Box::new(SharedMatchArg(shared.clone()));
+    shared
+}
+
+/// Expectation builder returned by `expect_*` methods of mock.
+///
+/// Expectation is added to scenario by `expect_*` method itself, so it is
+/// ordered relative to other expectations by the place of `expect_*` call,
+/// and builder only configures it. Method is expected to be called once
+/// with any arguments until `with` and `times` are used. Result must be
+/// given with `returning`, otherwise expectation isn't satisfied.
+pub struct ExpectationBuilder0<Res: 'static> {
+    slot: Rc<RefCell<BuilderSlot<Res>>>,
+}
+impl<Res: 'static> ExpectationBuilder0<Res> {
+    pub fn new(scenario: Rc<RefCell<ScenarioInternals>>, call_match: CallMatch0<Res>) -> Self {
+        ExpectationBuilder0 { slot: BuilderSlot::register(&scenario, call_match) }
+    }
+
+    pub fn returning<F>(self, mut func: F) -> Self
+            where F: FnMut() -> Res + 'static {
+        self.slot.borrow_mut().action = Some(Box::new(move |call| {
+            let _args = CallMatch0::<Res>::get_args(call);
+            func()
+        }));
+        self
+    }
+
+    pub fn times(self, number: usize) -> Self {
+        self.slot.borrow_mut().number = number;
+        self
+    }
+}
+
 #[must_use]
-pub struct CallMatch1<Arg0, Res> {
+pub struct CallMatch1<Arg0: ?Sized + 'static, Res, Kind0 = ByValue> {
     mock_id: usize,
     mock_type_id: usize,
     method_name: &'static str,
+    type_params: TypeParams,
+    arg_names: &'static [&'static str],
     arg0: Box<MatchArg<Arg0>>,
+    predicate: Option<Box<Fn(&Call) -> bool>>,
 
-    _phantom: PhantomData<Res>,
+    _phantom: PhantomData<(Res, Kind0)>,
 }
-impl<Arg0, Res> CallMatch1<Arg0, Res> {
-    pub fn new(mock_id: usize, mock_type_id: usize, method_name: &'static str, arg0: Box<MatchArg<Arg0>>) -> Self {
+impl<Arg0: ?Sized + 'static, Res, Kind0> CallMatch1<Arg0, Res, Kind0>
+    where Kind0: for<'a> ArgKind<'a, Arg0> {
+    pub fn new(mock_id: usize, mock_type_id: usize, method_name: &'static str,
+               type_params: TypeParams, arg_names: &'static [&'static str],
+               arg0: Box<MatchArg<Arg0>>) -> Self {
         CallMatch1 {
             mock_id: mock_id,
             mock_type_id: mock_type_id,
             method_name: method_name,
+            type_params: type_params,
+            arg_names: arg_names,
             arg0: arg0,
+            predicate: None,
             _phantom: PhantomData
         }
     }
 
-    fn get_args_ref(call: &Call) -> &mut (Arg0,) {
-        unsafe { &mut *(call.args_ptr as *mut (Arg0,)) }
+    fn get_args_ref(call: &Call) -> &mut (<Kind0 as ArgKind<'static, Arg0>>::Arg,) {
+        unsafe { &mut *(call.args_ptr as *mut (<Kind0 as ArgKind<'static, Arg0>>::Arg,)) }
     }
 
-    fn get_args(mut call: Call) -> Box<(Arg0,)> {
-        unsafe { Box::from_raw(call.take_args() as *mut (Arg0,)) }
+    fn get_args(mut call: Call) -> Box<(<Kind0 as ArgKind<'static, Arg0>>::Arg,)> {
+        unsafe { Box::from_raw(call.take_args() as *mut (<Kind0 as ArgKind<'static, Arg0>>::Arg,)) }
     }
 }
-impl<Arg0, Res> CallMatch for CallMatch1<Arg0, Res> {
+impl<Arg0: ?Sized + 'static, Res, Kind0> CallMatch for CallMatch1<Arg0, Res, Kind0>
+    where Kind0: for<'a> ArgKind<'a, Arg0> {
     fn matches_args(&self, call: &Call) -> bool {
         assert!(call.mock_type_id == self.mock_type_id &&
-                call.method_name == self.method_name);
+                call.method_name == self.method_name &&
+                call.type_params == self.type_params);
 
         let args = Self::get_args_ref(call);
-        self.arg0.matches(&args.0).is_ok()
+        self.arg0.matches(<Kind0 as ArgKind<'static, Arg0>>::matched(&args.0)).is_ok() &&
+        self.predicate.as_ref().map_or(true, |predicate| predicate(call))
     }
     fn validate(&self, call: &Call) -> Vec<Result<(), String>> {
         let args = Self::get_args_ref(call);
-        vec![ self.arg0.matches(&args.0) ]
+        let mut results = vec![ self.arg0.matches(<Kind0 as ArgKind<'static, Arg0>>::matched(&args.0)) ];
+        results.extend(validate_predicate(&self.predicate, call));
+        results
     }
     fn get_mock_id(&self) -> usize { self.mock_id }
     fn get_mock_type_id(&self) -> usize { self.mock_type_id }
     fn get_method_name(&self) -> &'static str { self.method_name }
+    fn get_type_params(&self) -> TypeParams { self.type_params }
     fn describe(&self) -> String {
-        format!("{}({})", self.get_method_name(),
-                          self.arg0.describe())
+        let args = [
+                     describe_arg(self.arg_names, 0, &self.arg0.describe())
+        ];
+        let predicate = if self.predicate.is_some() { " if <predicate>" } else { "" };
+        format!("{}({}){}", self.get_method_name(), args.join(", "), predicate)
     }
 }
 
 #[must_use]
-pub struct Reaction1<Arg0, Res> {
-    call_match: CallMatch1<Arg0, Res>,
-    action: ActionClone1<Arg0, Res>,
+pub struct Reaction1<Arg0: ?Sized + 'static, Res, Kind0 = ByValue> {
+    call_match: CallMatch1<Arg0, Res, Kind0>,
+    action: ArgsActionClone<Res>,
 }
-impl<Arg0, Res> Reaction1<Arg0, Res> {
-    pub fn times(self, number: usize) -> ExpectationTimes1<Arg0, Res> {
+impl<Arg0: ?Sized + 'static, Res, Kind0> Reaction1<Arg0, Res, Kind0> {
+    pub fn times(self, number: usize) -> ExpectationTimes1<Arg0, Res, Kind0> {
         ExpectationTimes1::new(self.call_match, self.action, number)
     }
 }
 
 #[must_use]
-pub struct ExpectationTimes1<Arg0, Res> {
-    action: ActionClone1<Arg0, Res>,
-    call_match: CallMatch1<Arg0, Res>,
+pub struct ExpectationTimes1<Arg0: ?Sized + 'static, Res, Kind0 = ByValue> {
+    action: ArgsActionClone<Res>,
+    call_match: CallMatch1<Arg0, Res, Kind0>,
     number: usize,
     count: usize,
 }
-impl<Arg0, Res> ExpectationTimes1<Arg0, Res> {
-    fn new(call_match: CallMatch1<Arg0, Res>, action: ActionClone1<Arg0, Res>, number: usize) -> Self {
+impl<Arg0: ?Sized + 'static, Res, Kind0> ExpectationTimes1<Arg0, Res, Kind0> {
+    fn new(call_match: CallMatch1<Arg0, Res, Kind0>, action: ArgsActionClone<Res>, number: usize) -> Self {
         ExpectationTimes1 { call_match: call_match, action: action, number: number, count: 0 }
     }
 }
-impl<Arg0, Res: Clone> Expectation for ExpectationTimes1<Arg0, Res> {
+impl<Arg0: ?Sized + 'static, Res, Kind0> Expectation for ExpectationTimes1<Arg0, Res, Kind0>
+    where Kind0: for<'a> ArgKind<'a, Arg0> {
     fn call_match(&self) -> &CallMatch {
         &self.call_match
     }
@@ -308,9 +566,7 @@ impl<Arg0, Res: Clone> Expectation for ExpectationTimes1<Arg0, Res> {
                    mock_name, self.call_match().get_method_name(), self.count, self.number);
         }
         self.count += 1;
-        // nightly: let box (arg0,) = CallMatch1::<Arg0, Res>::get_args(call);
-        let (arg0,) = *CallMatch1::<Arg0, Res>::get_args(call);
-        let result = (self.action)(arg0);
+        let result = (self.action)(call);
         Box::into_raw(Box::new(result)) as *mut u8
     }
     fn describe(&self) -> String {
@@ -320,11 +576,12 @@ impl<Arg0, Res: Clone> Expectation for ExpectationTimes1<Arg0, Res> {
 }
 
 #[must_use]
-pub struct Expectation1<Arg0, Res> {
-    call_match: CallMatch1<Arg0, Res>,
-    action: Option<Action1<Arg0, Res>>,
+pub struct Expectation1<Arg0: ?Sized + 'static, Res, Kind0 = ByValue> {
+    call_match: CallMatch1<Arg0, Res, Kind0>,
+    action: Option<ArgsAction<Res>>,
 }
-impl<Arg0, Res> Expectation for Expectation1<Arg0, Res> {
+impl<Arg0: ?Sized + 'static, Res, Kind0> Expectation for Expectation1<Arg0, Res, Kind0>
+    where Kind0: for<'a> ArgKind<'a, Arg0> {
     fn call_match(&self) -> &CallMatch {
         &self.call_match
     }
@@ -334,9 +591,7 @@ impl<Arg0, Res> Expectation for Expectation1<Arg0, Res> {
     fn satisfy(&mut self, call: Call, mock_name: &str) -> *mut u8 {
         match self.action.take() {
             Some(action) => {
-                // nightly: let box (arg0,) = CallMatch1::<Arg0, Res>::get_args(call);
-                let (arg0,) = *CallMatch1::<Arg0, Res>::get_args(call);
-                let result = action.call(arg0);
+                let result = action.call(call);
                 Box::into_raw(Box::new(result)) as *mut u8
             },
             None => {
@@ -348,116 +603,159 @@ impl<Arg0, Res> Expectation for Expectation1<Arg0, Res> {
         self.call_match.describe()
     }
 }
-impl<Arg0, Res: 'static> CallMatch1<Arg0, Res> {
-    pub fn and_return(self, result: Res) -> Expectation1<Arg0, Res> {
-        Expectation1 { call_match: self, action: Some(Action1::new(move |_| result)) }
-    }
-
-    pub fn and_panic(self, msg: String) -> Expectation1<Arg0, Res> {
-        Expectation1 { call_match: self, action: Some(Action1::new(move |_| panic!(msg))) }
+impl<Arg0: ?Sized + 'static, Res: 'static, Kind0: 'static> CallMatch1<Arg0, Res, Kind0>
+    where Kind0: for<'a> ArgKind<'a, Arg0> {
+    pub fn and_return(self, result: Res) -> Expectation1<Arg0, Res, Kind0> {
+        Expectation1 { call_match: self, action: Some(ArgsAction::new(move |_| result)) }
     }
 
-    pub fn and_call<F>(self, func: F) -> Expectation1<Arg0, Res>
-            where F: FnOnce(Arg0) -> Res + 'static {
-        Expectation1 { call_match: self, action: Some(Action1::new(func)) }
+    pub fn and_panic(self, msg: String) -> Expectation1<Arg0, Res, Kind0> {
+        Expectation1 { call_match: self, action: Some(ArgsAction::new(move |_| panic!(msg))) }
     }
 }
-impl<Arg0, Res: Clone + 'static> CallMatch1<Arg0, Res> {
-    pub fn and_return_clone(self, result: Res) -> Reaction1<Arg0, Res> {
+impl<Arg0: ?Sized + 'static, Res: Clone + 'static, Kind0: 'static> CallMatch1<Arg0, Res, Kind0>
+    where Kind0: for<'a> ArgKind<'a, Arg0> {
+    pub fn and_return_clone(self, result: Res) -> Reaction1<Arg0, Res, Kind0> {
         Reaction1 { call_match: self, action: Box::new(move |_| result.clone()) }
     }
-
-    pub fn and_call_clone<F>(self, func: F) -> Reaction1<Arg0, Res>
-            where F: FnMut(Arg0) -> Res + 'static {
-        Reaction1 { call_match: self, action: Box::new(func) }
-    }
 }
-impl<Arg0, Res: Default + 'static> CallMatch1<Arg0, Res> {
-    pub fn and_return_default(self) -> Reaction1<Arg0, Res> {
+impl<Arg0: ?Sized + 'static, Res: Default + 'static, Kind0: 'static> CallMatch1<Arg0, Res, Kind0>
+    where Kind0: for<'a> ArgKind<'a, Arg0> {
+    pub fn and_return_default(self) -> Reaction1<Arg0, Res, Kind0> {
         Reaction1 { call_match: self, action: Box::new(|_| Res::default()) }
     }
 }
 
+/// Expectation builder returned by `expect_*` methods of mock, see
+/// `ExpectationBuilder0`.
+pub struct ExpectationBuilder1<Arg0: ?Sized + 'static, Res: 'static, Kind0: 'static = ByValue> {
+    slot: Rc<RefCell<BuilderSlot<Res>>>,
+    arg0: Rc<RefCell<Box<MatchArg<Arg0>>>>,
+    _phantom: PhantomData<(Kind0,)>,
+}
+impl<Arg0: ?Sized + 'static, Res: 'static, Kind0: 'static> ExpectationBuilder1<Arg0, Res, Kind0>
+    where Kind0: for<'a> ArgKind<'a, Arg0> {
+    pub fn new(scenario: Rc<RefCell<ScenarioInternals>>, mut call_match: CallMatch1<Arg0, Res, Kind0>) -> Self {
+        let arg0 = share_matcher(&mut call_match.arg0);
+        ExpectationBuilder1 {
+            slot: BuilderSlot::register(&scenario, call_match),
+            arg0: arg0,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets argument matchers, all arguments match anything by default.
+    pub fn with<M0: MatchArg<Arg0> + 'static>(self, arg0: M0) -> Self {
+        *self.arg0.borrow_mut() = Box::new(arg0);
+        self
+    }
+
+    pub fn times(self, number: usize) -> Self {
+        self.slot.borrow_mut().number = number;
+        self
+    }
+}
+
+kind_impls!(CallMatch1, Expectation1, Reaction1, ExpectationBuilder1; Arg0 arg0);
+
 #[must_use]
-pub struct CallMatch2<Arg0, Arg1, Res> {
+pub struct CallMatch2<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Res, Kind0 = ByValue, Kind1 = ByValue> {
     mock_id: usize,
     mock_type_id: usize,
     method_name: &'static str,
+    type_params: TypeParams,
+    arg_names: &'static [&'static str],
     arg0: Box<MatchArg<Arg0>>,
     arg1: Box<MatchArg<Arg1>>,
+    predicate: Option<Box<Fn(&Call) -> bool>>,
 
-    _phantom: PhantomData<Res>,
+    _phantom: PhantomData<(Res, Kind0, Kind1)>,
 }
-impl<Arg0, Arg1, Res> CallMatch2<Arg0, Arg1, Res> {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Res, Kind0, Kind1> CallMatch2<Arg0, Arg1, Res, Kind0, Kind1>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1> {
     pub fn new(mock_id: usize, mock_type_id: usize, method_name: &'static str,
+               type_params: TypeParams, arg_names: &'static [&'static str],
                arg0: Box<MatchArg<Arg0>>,
                arg1: Box<MatchArg<Arg1>>) -> Self {
         CallMatch2 {
             mock_id: mock_id,
             mock_type_id: mock_type_id,
             method_name: method_name,
+            type_params: type_params,
+            arg_names: arg_names,
             arg0: arg0,
             arg1: arg1,
+            predicate: None,
             _phantom: PhantomData
         }
     }
 
-    fn get_args_ref(call: &Call) -> &mut (Arg0, Arg1) {
-        unsafe { &mut *(call.args_ptr as *mut (Arg0, Arg1)) }
+    fn get_args_ref(call: &Call) -> &mut (<Kind0 as ArgKind<'static, Arg0>>::Arg, <Kind1 as ArgKind<'static, Arg1>>::Arg) {
+        unsafe { &mut *(call.args_ptr as *mut (<Kind0 as ArgKind<'static, Arg0>>::Arg, <Kind1 as ArgKind<'static, Arg1>>::Arg)) }
     }
 
-    fn get_args(mut call: Call) -> Box<(Arg0, Arg1)> {
-        unsafe { Box::from_raw(call.take_args() as *mut (Arg0, Arg1)) }
+    fn get_args(mut call: Call) -> Box<(<Kind0 as ArgKind<'static, Arg0>>::Arg, <Kind1 as ArgKind<'static, Arg1>>::Arg)> {
+        unsafe { Box::from_raw(call.take_args() as *mut (<Kind0 as ArgKind<'static, Arg0>>::Arg, <Kind1 as ArgKind<'static, Arg1>>::Arg)) }
     }
 }
-impl<Arg0, Arg1, Res> CallMatch for CallMatch2<Arg0, Arg1, Res> {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Res, Kind0, Kind1> CallMatch for CallMatch2<Arg0, Arg1, Res, Kind0, Kind1>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1> {
     fn matches_args(&self, call: &Call) -> bool {
         assert!(call.mock_type_id == self.mock_type_id &&
-                call.method_name == self.method_name);
+                call.method_name == self.method_name &&
+                call.type_params == self.type_params);
 
         let args = Self::get_args_ref(call);
-        self.arg0.matches(&args.0).is_ok() &&
-        self.arg1.matches(&args.1).is_ok()
+        self.arg0.matches(<Kind0 as ArgKind<'static, Arg0>>::matched(&args.0)).is_ok() &&
+        self.arg1.matches(<Kind1 as ArgKind<'static, Arg1>>::matched(&args.1)).is_ok() &&
+        self.predicate.as_ref().map_or(true, |predicate| predicate(call))
     }
     fn validate(&self, call: &Call) -> Vec<Result<(), String>> {
         let args = Self::get_args_ref(call);
-        vec![ self.arg0.matches(&args.0),
-              self.arg1.matches(&args.1) ]
+        let mut results = vec![ self.arg0.matches(<Kind0 as ArgKind<'static, Arg0>>::matched(&args.0)),
+                                self.arg1.matches(<Kind1 as ArgKind<'static, Arg1>>::matched(&args.1)) ];
+        results.extend(validate_predicate(&self.predicate, call));
+        results
     }
     fn get_mock_id(&self) -> usize { self.mock_id }
     fn get_mock_type_id(&self) -> usize { self.mock_type_id }
     fn get_method_name(&self) -> &'static str { self.method_name }
+    fn get_type_params(&self) -> TypeParams { self.type_params }
     fn describe(&self) -> String {
-        format!("{}({}, {})", self.get_method_name(),
-                              self.arg0.describe(),
-                              self.arg1.describe())
+        let args = [
+                     describe_arg(self.arg_names, 0, &self.arg0.describe()),
+                     describe_arg(self.arg_names, 1, &self.arg1.describe())
+        ];
+        let predicate = if self.predicate.is_some() { " if <predicate>" } else { "" };
+        format!("{}({}){}", self.get_method_name(), args.join(", "), predicate)
     }
 }
 
 #[must_use]
-pub struct Reaction2<Arg0, Arg1, Res> {
-    call_match: CallMatch2<Arg0, Arg1, Res>,
-    action: ActionClone2<Arg0, Arg1, Res>,
+pub struct Reaction2<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Res, Kind0 = ByValue, Kind1 = ByValue> {
+    call_match: CallMatch2<Arg0, Arg1, Res, Kind0, Kind1>,
+    action: ArgsActionClone<Res>,
 }
-impl<Arg0, Arg1, Res> Reaction2<Arg0, Arg1, Res> {
-    pub fn times(self, number: usize) -> ExpectationTimes2<Arg0, Arg1, Res> {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Res, Kind0, Kind1> Reaction2<Arg0, Arg1, Res, Kind0, Kind1> {
+    pub fn times(self, number: usize) -> ExpectationTimes2<Arg0, Arg1, Res, Kind0, Kind1> {
         ExpectationTimes2::new(self.call_match, self.action, number)
     }
 }
 
 #[must_use]
-pub struct ExpectationTimes2<Arg0, Arg1, Res> {
-    action: ActionClone2<Arg0, Arg1, Res>,
-    call_match: CallMatch2<Arg0, Arg1, Res>,
+pub struct ExpectationTimes2<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Res, Kind0 = ByValue, Kind1 = ByValue> {
+    action: ArgsActionClone<Res>,
+    call_match: CallMatch2<Arg0, Arg1, Res, Kind0, Kind1>,
     number: usize,
     count: usize,
 }
-impl<Arg0, Arg1, Res> ExpectationTimes2<Arg0, Arg1, Res> {
-    fn new(call_match: CallMatch2<Arg0, Arg1, Res>, action: ActionClone2<Arg0, Arg1, Res>, number: usize) -> Self {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Res, Kind0, Kind1> ExpectationTimes2<Arg0, Arg1, Res, Kind0, Kind1> {
+    fn new(call_match: CallMatch2<Arg0, Arg1, Res, Kind0, Kind1>, action: ArgsActionClone<Res>, number: usize) -> Self {
         ExpectationTimes2 { call_match: call_match, action: action, number: number, count: 0 }
     }
 }
-impl<Arg0, Arg1, Res: Clone> Expectation for ExpectationTimes2<Arg0, Arg1, Res> {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Res, Kind0, Kind1> Expectation for ExpectationTimes2<Arg0, Arg1, Res, Kind0, Kind1>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1> {
     fn call_match(&self) -> &CallMatch {
         &self.call_match
     }
@@ -470,9 +768,7 @@ impl<Arg0, Arg1, Res: Clone> Expectation for ExpectationTimes2<Arg0, Arg1, Res>
                    mock_name, self.call_match().get_method_name(), self.count, self.number);
         }
         self.count += 1;
-        // nightly: let box (arg0, arg1) = CallMatch2::<Arg0, Arg1, Res>::get_args(call);
-        let (arg0, arg1) = *CallMatch2::<Arg0, Arg1, Res>::get_args(call);
-        let result = (self.action)(arg0, arg1);
+        let result = (self.action)(call);
         Box::into_raw(Box::new(result)) as *mut u8
     }
     fn describe(&self) -> String {
@@ -482,11 +778,12 @@ impl<Arg0, Arg1, Res: Clone> Expectation for ExpectationTimes2<Arg0, Arg1, Res>
 }
 
 #[must_use]
-pub struct Expectation2<Arg0, Arg1, Res> {
-    call_match: CallMatch2<Arg0, Arg1, Res>,
-    action: Option<Action2<Arg0, Arg1, Res>>,
+pub struct Expectation2<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Res, Kind0 = ByValue, Kind1 = ByValue> {
+    call_match: CallMatch2<Arg0, Arg1, Res, Kind0, Kind1>,
+    action: Option<ArgsAction<Res>>,
 }
-impl<Arg0, Arg1, Res> Expectation for Expectation2<Arg0, Arg1, Res> {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Res, Kind0, Kind1> Expectation for Expectation2<Arg0, Arg1, Res, Kind0, Kind1>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1> {
     fn call_match(&self) -> &CallMatch {
         &self.call_match
     }
@@ -496,9 +793,7 @@ impl<Arg0, Arg1, Res> Expectation for Expectation2<Arg0, Arg1, Res> {
     fn satisfy(&mut self, call: Call, mock_name: &str) -> *mut u8 {
         match self.action.take() {
             Some(action) => {
-                // nightly: let box (arg0, arg1) = CallMatch2::<Arg0, Arg1, Res>::get_args(call);
-                let (arg0, arg1) = *CallMatch2::<Arg0, Arg1, Res>::get_args(call);
-                let result = action.call(arg0, arg1);
+                let result = action.call(call);
                 Box::into_raw(Box::new(result)) as *mut u8
             },
             None => {
@@ -510,50 +805,84 @@ impl<Arg0, Arg1, Res> Expectation for Expectation2<Arg0, Arg1, Res> {
         self.call_match.describe()
     }
 }
-impl<Arg0, Arg1, Res: 'static> CallMatch2<Arg0, Arg1, Res> {
-    pub fn and_return(self, result: Res) -> Expectation2<Arg0, Arg1, Res> {
-        Expectation2 { call_match: self, action: Some(Action2::new(move |_, _| result)) }
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Res: 'static, Kind0: 'static, Kind1: 'static> CallMatch2<Arg0, Arg1, Res, Kind0, Kind1>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1> {
+    pub fn and_return(self, result: Res) -> Expectation2<Arg0, Arg1, Res, Kind0, Kind1> {
+        Expectation2 { call_match: self, action: Some(ArgsAction::new(move |_| result)) }
     }
 
-    pub fn and_panic(self, msg: String) -> Expectation2<Arg0, Arg1, Res> {
-        Expectation2 { call_match: self, action: Some(Action2::new(move |_, _| panic!(msg))) }
+    pub fn and_panic(self, msg: String) -> Expectation2<Arg0, Arg1, Res, Kind0, Kind1> {
+        Expectation2 { call_match: self, action: Some(ArgsAction::new(move |_| panic!(msg))) }
     }
-
-    pub fn and_call<F>(self, func: F) -> Expectation2<Arg0, Arg1, Res>
-            where F: FnOnce(Arg0, Arg1) -> Res + 'static {
-        Expectation2 { call_match: self, action: Some(Action2::new(func)) }
+}
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Res: Clone + 'static, Kind0: 'static, Kind1: 'static> CallMatch2<Arg0, Arg1, Res, Kind0, Kind1>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1> {
+    pub fn and_return_clone(self, result: Res) -> Reaction2<Arg0, Arg1, Res, Kind0, Kind1> {
+        Reaction2 { call_match: self, action: Box::new(move |_| result.clone()) }
     }
 }
-impl<Arg0, Arg1, Res: Clone + 'static> CallMatch2<Arg0, Arg1, Res> {
-    pub fn and_return_clone(self, result: Res) -> Reaction2<Arg0, Arg1, Res> {
-        Reaction2 { call_match: self, action: Box::new(move |_, _| result.clone()) }
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Res: Default + 'static, Kind0: 'static, Kind1: 'static> CallMatch2<Arg0, Arg1, Res, Kind0, Kind1>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1> {
+    pub fn and_return_default(self) -> Reaction2<Arg0, Arg1, Res, Kind0, Kind1> {
+        Reaction2 { call_match: self, action: Box::new(|_| Res::default()) }
     }
+}
 
-    pub fn and_call_clone<F>(self, func: F) -> Reaction2<Arg0, Arg1, Res>
-            where F: FnMut(Arg0, Arg1) -> Res + 'static {
-        Reaction2 { call_match: self, action: Box::new(func) }
-    }
+/// Expectation builder returned by `expect_*` methods of mock, see
+/// `ExpectationBuilder0`.
+pub struct ExpectationBuilder2<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Res: 'static, Kind0: 'static = ByValue, Kind1: 'static = ByValue> {
+    slot: Rc<RefCell<BuilderSlot<Res>>>,
+    arg0: Rc<RefCell<Box<MatchArg<Arg0>>>>,
+    arg1: Rc<RefCell<Box<MatchArg<Arg1>>>>,
+    _phantom: PhantomData<(Kind0, Kind1)>,
 }
-impl<Arg0, Arg1, Res: Default + 'static> CallMatch2<Arg0, Arg1, Res> {
-    pub fn and_return_default(self) -> Reaction2<Arg0, Arg1, Res> {
-        Reaction2 { call_match: self, action: Box::new(|_, _| Res::default()) }
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Res: 'static, Kind0: 'static, Kind1: 'static> ExpectationBuilder2<Arg0, Arg1, Res, Kind0, Kind1>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1> {
+    pub fn new(scenario: Rc<RefCell<ScenarioInternals>>, mut call_match: CallMatch2<Arg0, Arg1, Res, Kind0, Kind1>) -> Self {
+        let arg0 = share_matcher(&mut call_match.arg0);
+        let arg1 = share_matcher(&mut call_match.arg1);
+        ExpectationBuilder2 {
+            slot: BuilderSlot::register(&scenario, call_match),
+            arg0: arg0,
+            arg1: arg1,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets argument matchers, all arguments match anything by default.
+    pub fn with<M0: MatchArg<Arg0> + 'static, M1: MatchArg<Arg1> + 'static>(self, arg0: M0, arg1: M1) -> Self {
+        *self.arg0.borrow_mut() = Box::new(arg0);
+        *self.arg1.borrow_mut() = Box::new(arg1);
+        self
+    }
+
+    pub fn times(self, number: usize) -> Self {
+        self.slot.borrow_mut().number = number;
+        self
     }
 }
 
+kind_impls!(CallMatch2, Expectation2, Reaction2, ExpectationBuilder2; Arg0 arg0, Arg1 arg1);
 
 #[must_use]
-pub struct CallMatch3<Arg0, Arg1, Arg2, Res> {
+pub struct CallMatch3<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Res, Kind0 = ByValue, Kind1 = ByValue, Kind2 = ByValue> {
     mock_id: usize,
     mock_type_id: usize,
     method_name: &'static str,
+    type_params: TypeParams,
+    arg_names: &'static [&'static str],
     arg0: Box<MatchArg<Arg0>>,
     arg1: Box<MatchArg<Arg1>>,
     arg2: Box<MatchArg<Arg2>>,
+    predicate: Option<Box<Fn(&Call) -> bool>>,
 
-    _phantom: PhantomData<Res>,
+    _phantom: PhantomData<(Res, Kind0, Kind1, Kind2)>,
 }
-impl<Arg0, Arg1, Arg2, Res> CallMatch3<Arg0, Arg1, Arg2, Res> {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Res, Kind0, Kind1, Kind2> CallMatch3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2> {
+    #[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
     pub fn new(mock_id: usize, mock_type_id: usize, method_name: &'static str,
+               type_params: TypeParams, arg_names: &'static [&'static str],
                arg0: Box<MatchArg<Arg0>>,
                arg1: Box<MatchArg<Arg1>>,
                arg2: Box<MatchArg<Arg2>>) -> Self {
@@ -561,72 +890,87 @@ impl<Arg0, Arg1, Arg2, Res> CallMatch3<Arg0, Arg1, Arg2, Res> {
             mock_id: mock_id,
             mock_type_id: mock_type_id,
             method_name: method_name,
+            type_params: type_params,
+            arg_names: arg_names,
             arg0: arg0,
             arg1: arg1,
             arg2: arg2,
+            predicate: None,
             _phantom: PhantomData
         }
     }
 
-    fn get_args_ref(call: &Call) -> &(Arg0, Arg1, Arg2) {
-        unsafe { &mut *(call.args_ptr as *mut (Arg0, Arg1, Arg2)) }
+    #[cfg_attr(feature = "cargo-clippy", allow(type_complexity))]
+    fn get_args_ref(call: &Call) -> &mut (<Kind0 as ArgKind<'static, Arg0>>::Arg, <Kind1 as ArgKind<'static, Arg1>>::Arg, <Kind2 as ArgKind<'static, Arg2>>::Arg) {
+        unsafe { &mut *(call.args_ptr as *mut (<Kind0 as ArgKind<'static, Arg0>>::Arg, <Kind1 as ArgKind<'static, Arg1>>::Arg, <Kind2 as ArgKind<'static, Arg2>>::Arg)) }
     }
 
-    fn get_args(mut call: Call) -> Box<(Arg0, Arg1, Arg2)> {
-        unsafe { Box::from_raw(call.take_args() as *mut (Arg0, Arg1, Arg2)) }
+    #[cfg_attr(feature = "cargo-clippy", allow(type_complexity))]
+    fn get_args(mut call: Call) -> Box<(<Kind0 as ArgKind<'static, Arg0>>::Arg, <Kind1 as ArgKind<'static, Arg1>>::Arg, <Kind2 as ArgKind<'static, Arg2>>::Arg)> {
+        unsafe { Box::from_raw(call.take_args() as *mut (<Kind0 as ArgKind<'static, Arg0>>::Arg, <Kind1 as ArgKind<'static, Arg1>>::Arg, <Kind2 as ArgKind<'static, Arg2>>::Arg)) }
     }
 }
-impl<Arg0, Arg1, Arg2, Res> CallMatch for CallMatch3<Arg0, Arg1, Arg2, Res> {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Res, Kind0, Kind1, Kind2> CallMatch for CallMatch3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2> {
     fn matches_args(&self, call: &Call) -> bool {
         assert!(call.mock_type_id == self.mock_type_id &&
-                call.method_name == self.method_name);
+                call.method_name == self.method_name &&
+                call.type_params == self.type_params);
 
         let args = Self::get_args_ref(call);
-        self.arg0.matches(&args.0).is_ok() &&
-        self.arg1.matches(&args.1).is_ok() &&
-        self.arg2.matches(&args.2).is_ok()
+        self.arg0.matches(<Kind0 as ArgKind<'static, Arg0>>::matched(&args.0)).is_ok() &&
+        self.arg1.matches(<Kind1 as ArgKind<'static, Arg1>>::matched(&args.1)).is_ok() &&
+        self.arg2.matches(<Kind2 as ArgKind<'static, Arg2>>::matched(&args.2)).is_ok() &&
+        self.predicate.as_ref().map_or(true, |predicate| predicate(call))
     }
     fn validate(&self, call: &Call) -> Vec<Result<(), String>> {
         let args = Self::get_args_ref(call);
-        vec![ self.arg0.matches(&args.0),
-              self.arg1.matches(&args.1),
-              self.arg2.matches(&args.2) ]
+        let mut results = vec![ self.arg0.matches(<Kind0 as ArgKind<'static, Arg0>>::matched(&args.0)),
+                                self.arg1.matches(<Kind1 as ArgKind<'static, Arg1>>::matched(&args.1)),
+                                self.arg2.matches(<Kind2 as ArgKind<'static, Arg2>>::matched(&args.2)) ];
+        results.extend(validate_predicate(&self.predicate, call));
+        results
     }
     fn get_mock_id(&self) -> usize { self.mock_id }
     fn get_mock_type_id(&self) -> usize { self.mock_type_id }
     fn get_method_name(&self) -> &'static str { self.method_name }
+    fn get_type_params(&self) -> TypeParams { self.type_params }
     fn describe(&self) -> String {
-        format!("{}({}, {}, {})", self.get_method_name(),
-                                  self.arg0.describe(),
-                                  self.arg1.describe(),
-                                  self.arg2.describe())
+        let args = [
+                     describe_arg(self.arg_names, 0, &self.arg0.describe()),
+                     describe_arg(self.arg_names, 1, &self.arg1.describe()),
+                     describe_arg(self.arg_names, 2, &self.arg2.describe())
+        ];
+        let predicate = if self.predicate.is_some() { " if <predicate>" } else { "" };
+        format!("{}({}){}", self.get_method_name(), args.join(", "), predicate)
     }
 }
 
 #[must_use]
-pub struct Reaction3<Arg0, Arg1, Arg2, Res> {
-    call_match: CallMatch3<Arg0, Arg1, Arg2, Res>,
-    action: ActionClone3<Arg0, Arg1, Arg2, Res>,
+pub struct Reaction3<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Res, Kind0 = ByValue, Kind1 = ByValue, Kind2 = ByValue> {
+    call_match: CallMatch3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2>,
+    action: ArgsActionClone<Res>,
 }
-impl<Arg0, Arg1, Arg2, Res> Reaction3<Arg0, Arg1, Arg2, Res> {
-    pub fn times(self, number: usize) -> ExpectationTimes3<Arg0, Arg1, Arg2, Res> {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Res, Kind0, Kind1, Kind2> Reaction3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2> {
+    pub fn times(self, number: usize) -> ExpectationTimes3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2> {
         ExpectationTimes3::new(self.call_match, self.action, number)
     }
 }
 
 #[must_use]
-pub struct ExpectationTimes3<Arg0, Arg1, Arg2, Res> {
-    action: ActionClone3<Arg0, Arg1, Arg2, Res>,
-    call_match: CallMatch3<Arg0, Arg1, Arg2, Res>,
+pub struct ExpectationTimes3<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Res, Kind0 = ByValue, Kind1 = ByValue, Kind2 = ByValue> {
+    action: ArgsActionClone<Res>,
+    call_match: CallMatch3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2>,
     number: usize,
     count: usize,
 }
-impl<Arg0, Arg1, Arg2, Res> ExpectationTimes3<Arg0, Arg1, Arg2, Res> {
-    fn new(call_match: CallMatch3<Arg0, Arg1, Arg2, Res>, action: ActionClone3<Arg0, Arg1, Arg2, Res>, number: usize) -> Self {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Res, Kind0, Kind1, Kind2> ExpectationTimes3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2> {
+    fn new(call_match: CallMatch3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2>, action: ArgsActionClone<Res>, number: usize) -> Self {
         ExpectationTimes3 { call_match: call_match, action: action, number: number, count: 0 }
     }
 }
-impl<Arg0, Arg1, Arg2, Res> Expectation for ExpectationTimes3<Arg0, Arg1, Arg2, Res> {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Res, Kind0, Kind1, Kind2> Expectation for ExpectationTimes3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2> {
     fn call_match(&self) -> &CallMatch {
         &self.call_match
     }
@@ -639,9 +983,7 @@ impl<Arg0, Arg1, Arg2, Res> Expectation for ExpectationTimes3<Arg0, Arg1, Arg2,
                    mock_name, self.call_match().get_method_name(), self.count, self.number);
         }
         self.count += 1;
-        // nightly: let box (arg0, arg1, arg2) = CallMatch3::<Arg0, Arg1, Arg2, Res>::get_args(call);
-        let (arg0, arg1, arg2) = *CallMatch3::<Arg0, Arg1, Arg2, Res>::get_args(call);
-        let result = (self.action)(arg0, arg1, arg2);
+        let result = (self.action)(call);
         Box::into_raw(Box::new(result)) as *mut u8
     }
     fn describe(&self) -> String {
@@ -651,11 +993,12 @@ impl<Arg0, Arg1, Arg2, Res> Expectation for ExpectationTimes3<Arg0, Arg1, Arg2,
 }
 
 #[must_use]
-pub struct Expectation3<Arg0, Arg1, Arg2, Res> {
-    call_match: CallMatch3<Arg0, Arg1, Arg2, Res>,
-    action: Option<Action3<Arg0, Arg1, Arg2, Res>>,
+pub struct Expectation3<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Res, Kind0 = ByValue, Kind1 = ByValue, Kind2 = ByValue> {
+    call_match: CallMatch3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2>,
+    action: Option<ArgsAction<Res>>,
 }
-impl<Arg0, Arg1, Arg2, Res> Expectation for Expectation3<Arg0, Arg1, Arg2, Res> {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Res, Kind0, Kind1, Kind2> Expectation for Expectation3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2> {
     fn call_match(&self) -> &CallMatch {
         &self.call_match
     }
@@ -665,9 +1008,7 @@ impl<Arg0, Arg1, Arg2, Res> Expectation for Expectation3<Arg0, Arg1, Arg2, Res>
     fn satisfy(&mut self, call: Call, mock_name: &str) -> *mut u8 {
         match self.action.take() {
             Some(action) => {
-                // nightly: let box (arg0, arg1, arg2) = CallMatch3::<Arg0, Arg1, Arg2, Res>::get_args(call);
-                let (arg0, arg1, arg2) = *CallMatch3::<Arg0, Arg1, Arg2, Res>::get_args(call);
-                let result = action.call(arg0, arg1, arg2);
+                let result = action.call(call);
                 Box::into_raw(Box::new(result)) as *mut u8
             },
             None => {
@@ -679,50 +1020,89 @@ impl<Arg0, Arg1, Arg2, Res> Expectation for Expectation3<Arg0, Arg1, Arg2, Res>
         self.call_match.describe()
     }
 }
-impl<Arg0, Arg1, Arg2, Res: 'static> CallMatch3<Arg0, Arg1, Arg2, Res> {
-    pub fn and_return(self, result: Res) -> Expectation3<Arg0, Arg1, Arg2, Res> {
-        Expectation3 { call_match: self, action: Some(Action3::new(move |_, _, _| result)) }
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Res: 'static, Kind0: 'static, Kind1: 'static, Kind2: 'static> CallMatch3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2> {
+    pub fn and_return(self, result: Res) -> Expectation3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2> {
+        Expectation3 { call_match: self, action: Some(ArgsAction::new(move |_| result)) }
     }
 
-    pub fn and_panic(self, msg: String) -> Expectation3<Arg0, Arg1, Arg2, Res> {
-        Expectation3 { call_match: self, action: Some(Action3::new(move |_, _, _| panic!(msg))) }
+    pub fn and_panic(self, msg: String) -> Expectation3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2> {
+        Expectation3 { call_match: self, action: Some(ArgsAction::new(move |_| panic!(msg))) }
     }
-
-    pub fn and_call<F>(self, func: F) -> Expectation3<Arg0, Arg1, Arg2, Res>
-            where F: FnOnce(Arg0, Arg1, Arg2) -> Res + 'static {
-        Expectation3 { call_match: self, action: Some(Action3::new(func)) }
+}
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Res: Clone + 'static, Kind0: 'static, Kind1: 'static, Kind2: 'static> CallMatch3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2> {
+    pub fn and_return_clone(self, result: Res) -> Reaction3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2> {
+        Reaction3 { call_match: self, action: Box::new(move |_| result.clone()) }
     }
 }
-impl<Arg0, Arg1, Arg2, Res: Clone + 'static> CallMatch3<Arg0, Arg1, Arg2, Res> {
-    pub fn and_return_clone(self, result: Res) -> Reaction3<Arg0, Arg1, Arg2, Res> {
-        Reaction3 { call_match: self, action: Box::new(move |_, _, _| result.clone()) }
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Res: Default + 'static, Kind0: 'static, Kind1: 'static, Kind2: 'static> CallMatch3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2> {
+    pub fn and_return_default(self) -> Reaction3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2> {
+        Reaction3 { call_match: self, action: Box::new(|_| Res::default()) }
     }
+}
 
-    pub fn and_call_clone<F>(self, func: F) -> Reaction3<Arg0, Arg1, Arg2, Res>
-            where F: FnMut(Arg0, Arg1, Arg2) -> Res + 'static {
-        Reaction3 { call_match: self, action: Box::new(func) }
-    }
+/// Expectation builder returned by `expect_*` methods of mock, see
+/// `ExpectationBuilder0`.
+pub struct ExpectationBuilder3<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Res: 'static, Kind0: 'static = ByValue, Kind1: 'static = ByValue, Kind2: 'static = ByValue> {
+    slot: Rc<RefCell<BuilderSlot<Res>>>,
+    arg0: Rc<RefCell<Box<MatchArg<Arg0>>>>,
+    arg1: Rc<RefCell<Box<MatchArg<Arg1>>>>,
+    arg2: Rc<RefCell<Box<MatchArg<Arg2>>>>,
+    _phantom: PhantomData<(Kind0, Kind1, Kind2)>,
 }
-impl<Arg0, Arg1, Arg2, Res: Default + 'static> CallMatch3<Arg0, Arg1, Arg2, Res> {
-    pub fn and_return_default(self) -> Reaction3<Arg0, Arg1, Arg2, Res> {
-        Reaction3 { call_match: self, action: Box::new(move |_, _, _| Res::default()) }
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Res: 'static, Kind0: 'static, Kind1: 'static, Kind2: 'static> ExpectationBuilder3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2> {
+    pub fn new(scenario: Rc<RefCell<ScenarioInternals>>, mut call_match: CallMatch3<Arg0, Arg1, Arg2, Res, Kind0, Kind1, Kind2>) -> Self {
+        let arg0 = share_matcher(&mut call_match.arg0);
+        let arg1 = share_matcher(&mut call_match.arg1);
+        let arg2 = share_matcher(&mut call_match.arg2);
+        ExpectationBuilder3 {
+            slot: BuilderSlot::register(&scenario, call_match),
+            arg0: arg0,
+            arg1: arg1,
+            arg2: arg2,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets argument matchers, all arguments match anything by default.
+    pub fn with<M0: MatchArg<Arg0> + 'static, M1: MatchArg<Arg1> + 'static, M2: MatchArg<Arg2> + 'static>(self, arg0: M0, arg1: M1, arg2: M2) -> Self {
+        *self.arg0.borrow_mut() = Box::new(arg0);
+        *self.arg1.borrow_mut() = Box::new(arg1);
+        *self.arg2.borrow_mut() = Box::new(arg2);
+        self
+    }
+
+    pub fn times(self, number: usize) -> Self {
+        self.slot.borrow_mut().number = number;
+        self
     }
 }
 
+kind_impls!(CallMatch3, Expectation3, Reaction3, ExpectationBuilder3; Arg0 arg0, Arg1 arg1, Arg2 arg2);
+
 #[must_use]
-pub struct CallMatch4<Arg0, Arg1, Arg2, Arg3, Res> {
+pub struct CallMatch4<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Arg3: ?Sized + 'static, Res, Kind0 = ByValue, Kind1 = ByValue, Kind2 = ByValue, Kind3 = ByValue> {
     mock_id: usize,
     mock_type_id: usize,
     method_name: &'static str,
+    type_params: TypeParams,
+    arg_names: &'static [&'static str],
     arg0: Box<MatchArg<Arg0>>,
     arg1: Box<MatchArg<Arg1>>,
     arg2: Box<MatchArg<Arg2>>,
     arg3: Box<MatchArg<Arg3>>,
+    predicate: Option<Box<Fn(&Call) -> bool>>,
 
-    _phantom: PhantomData<Res>,
+    _phantom: PhantomData<(Res, Kind0, Kind1, Kind2, Kind3)>,
 }
-impl<Arg0, Arg1, Arg2, Arg3, Res> CallMatch4<Arg0, Arg1, Arg2, Arg3, Res> {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Arg3: ?Sized + 'static, Res, Kind0, Kind1, Kind2, Kind3> CallMatch4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2>, Kind3: for<'a> ArgKind<'a, Arg3> {
+    #[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
     pub fn new(mock_id: usize, mock_type_id: usize, method_name: &'static str,
+               type_params: TypeParams, arg_names: &'static [&'static str],
                arg0: Box<MatchArg<Arg0>>,
                arg1: Box<MatchArg<Arg1>>,
                arg2: Box<MatchArg<Arg2>>,
@@ -731,76 +1111,91 @@ impl<Arg0, Arg1, Arg2, Arg3, Res> CallMatch4<Arg0, Arg1, Arg2, Arg3, Res> {
             mock_id: mock_id,
             mock_type_id: mock_type_id,
             method_name: method_name,
+            type_params: type_params,
+            arg_names: arg_names,
             arg0: arg0,
             arg1: arg1,
             arg2: arg2,
             arg3: arg3,
+            predicate: None,
             _phantom: PhantomData
         }
     }
 
-    fn get_args_ref(call: &Call) -> &(Arg0, Arg1, Arg2, Arg3) {
-        unsafe { &mut *(call.args_ptr as *mut (Arg0, Arg1, Arg2, Arg3)) }
+    #[cfg_attr(feature = "cargo-clippy", allow(type_complexity))]
+    fn get_args_ref(call: &Call) -> &mut (<Kind0 as ArgKind<'static, Arg0>>::Arg, <Kind1 as ArgKind<'static, Arg1>>::Arg, <Kind2 as ArgKind<'static, Arg2>>::Arg, <Kind3 as ArgKind<'static, Arg3>>::Arg) {
+        unsafe { &mut *(call.args_ptr as *mut (<Kind0 as ArgKind<'static, Arg0>>::Arg, <Kind1 as ArgKind<'static, Arg1>>::Arg, <Kind2 as ArgKind<'static, Arg2>>::Arg, <Kind3 as ArgKind<'static, Arg3>>::Arg)) }
     }
 
-    fn get_args(mut call: Call) -> Box<(Arg0, Arg1, Arg2, Arg3)> {
-        unsafe { Box::from_raw(call.take_args() as *mut (Arg0, Arg1, Arg2, Arg3)) }
+    #[cfg_attr(feature = "cargo-clippy", allow(type_complexity))]
+    fn get_args(mut call: Call) -> Box<(<Kind0 as ArgKind<'static, Arg0>>::Arg, <Kind1 as ArgKind<'static, Arg1>>::Arg, <Kind2 as ArgKind<'static, Arg2>>::Arg, <Kind3 as ArgKind<'static, Arg3>>::Arg)> {
+        unsafe { Box::from_raw(call.take_args() as *mut (<Kind0 as ArgKind<'static, Arg0>>::Arg, <Kind1 as ArgKind<'static, Arg1>>::Arg, <Kind2 as ArgKind<'static, Arg2>>::Arg, <Kind3 as ArgKind<'static, Arg3>>::Arg)) }
     }
 }
-impl<Arg0, Arg1, Arg2, Arg3, Res> CallMatch for CallMatch4<Arg0, Arg1, Arg2, Arg3, Res> {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Arg3: ?Sized + 'static, Res, Kind0, Kind1, Kind2, Kind3> CallMatch for CallMatch4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2>, Kind3: for<'a> ArgKind<'a, Arg3> {
     fn matches_args(&self, call: &Call) -> bool {
         assert!(call.mock_type_id == self.mock_type_id &&
-                call.method_name == self.method_name);
+                call.method_name == self.method_name &&
+                call.type_params == self.type_params);
 
         let args = Self::get_args_ref(call);
-        self.arg0.matches(&args.0).is_ok() &&
-        self.arg1.matches(&args.1).is_ok() &&
-        self.arg2.matches(&args.2).is_ok() &&
-        self.arg3.matches(&args.3).is_ok()
+        self.arg0.matches(<Kind0 as ArgKind<'static, Arg0>>::matched(&args.0)).is_ok() &&
+        self.arg1.matches(<Kind1 as ArgKind<'static, Arg1>>::matched(&args.1)).is_ok() &&
+        self.arg2.matches(<Kind2 as ArgKind<'static, Arg2>>::matched(&args.2)).is_ok() &&
+        self.arg3.matches(<Kind3 as ArgKind<'static, Arg3>>::matched(&args.3)).is_ok() &&
+        self.predicate.as_ref().map_or(true, |predicate| predicate(call))
     }
     fn validate(&self, call: &Call) -> Vec<Result<(), String>> {
         let args = Self::get_args_ref(call);
-        vec![ self.arg0.matches(&args.0),
-              self.arg1.matches(&args.1),
-              self.arg2.matches(&args.2),
-              self.arg3.matches(&args.3) ]
+        let mut results = vec![ self.arg0.matches(<Kind0 as ArgKind<'static, Arg0>>::matched(&args.0)),
+                                self.arg1.matches(<Kind1 as ArgKind<'static, Arg1>>::matched(&args.1)),
+                                self.arg2.matches(<Kind2 as ArgKind<'static, Arg2>>::matched(&args.2)),
+                                self.arg3.matches(<Kind3 as ArgKind<'static, Arg3>>::matched(&args.3)) ];
+        results.extend(validate_predicate(&self.predicate, call));
+        results
     }
     fn get_mock_id(&self) -> usize { self.mock_id }
     fn get_mock_type_id(&self) -> usize { self.mock_type_id }
     fn get_method_name(&self) -> &'static str { self.method_name }
+    fn get_type_params(&self) -> TypeParams { self.type_params }
     fn describe(&self) -> String {
-        format!("{}({}, {}, {}, {})", self.get_method_name(),
-                                      self.arg0.describe(),
-                                      self.arg1.describe(),
-                                      self.arg2.describe(),
-                                      self.arg3.describe())
+        let args = [
+                     describe_arg(self.arg_names, 0, &self.arg0.describe()),
+                     describe_arg(self.arg_names, 1, &self.arg1.describe()),
+                     describe_arg(self.arg_names, 2, &self.arg2.describe()),
+                     describe_arg(self.arg_names, 3, &self.arg3.describe())
+        ];
+        let predicate = if self.predicate.is_some() { " if <predicate>" } else { "" };
+        format!("{}({}){}", self.get_method_name(), args.join(", "), predicate)
     }
 }
 
 #[must_use]
-pub struct Reaction4<Arg0, Arg1, Arg2, Arg3, Res> {
-    call_match: CallMatch4<Arg0, Arg1, Arg2, Arg3, Res>,
-    action: ActionClone4<Arg0, Arg1, Arg2, Arg3, Res>,
+pub struct Reaction4<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Arg3: ?Sized + 'static, Res, Kind0 = ByValue, Kind1 = ByValue, Kind2 = ByValue, Kind3 = ByValue> {
+    call_match: CallMatch4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3>,
+    action: ArgsActionClone<Res>,
 }
-impl<Arg0, Arg1, Arg2, Arg3, Res> Reaction4<Arg0, Arg1, Arg2, Arg3, Res> {
-    pub fn times(self, number: usize) -> ExpectationTimes4<Arg0, Arg1, Arg2, Arg3, Res> {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Arg3: ?Sized + 'static, Res, Kind0, Kind1, Kind2, Kind3> Reaction4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3> {
+    pub fn times(self, number: usize) -> ExpectationTimes4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3> {
         ExpectationTimes4::new(self.call_match, self.action, number)
     }
 }
 
 #[must_use]
-pub struct ExpectationTimes4<Arg0, Arg1, Arg2, Arg3, Res> {
-    action: ActionClone4<Arg0, Arg1, Arg2, Arg3, Res>,
-    call_match: CallMatch4<Arg0, Arg1, Arg2, Arg3, Res>,
+pub struct ExpectationTimes4<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Arg3: ?Sized + 'static, Res, Kind0 = ByValue, Kind1 = ByValue, Kind2 = ByValue, Kind3 = ByValue> {
+    action: ArgsActionClone<Res>,
+    call_match: CallMatch4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3>,
     number: usize,
     count: usize,
 }
-impl<Arg0, Arg1, Arg2, Arg3, Res> ExpectationTimes4<Arg0, Arg1, Arg2, Arg3, Res> {
-    fn new(call_match: CallMatch4<Arg0, Arg1, Arg2, Arg3, Res>, action: ActionClone4<Arg0, Arg1, Arg2, Arg3, Res>, number: usize) -> Self {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Arg3: ?Sized + 'static, Res, Kind0, Kind1, Kind2, Kind3> ExpectationTimes4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3> {
+    fn new(call_match: CallMatch4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3>, action: ArgsActionClone<Res>, number: usize) -> Self {
         ExpectationTimes4 { call_match: call_match, action: action, number: number, count: 0 }
     }
 }
-impl<Arg0, Arg1, Arg2, Arg3, Res: Clone> Expectation for ExpectationTimes4<Arg0, Arg1, Arg2, Arg3, Res> {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Arg3: ?Sized + 'static, Res, Kind0, Kind1, Kind2, Kind3> Expectation for ExpectationTimes4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2>, Kind3: for<'a> ArgKind<'a, Arg3> {
     fn call_match(&self) -> &CallMatch {
         &self.call_match
     }
@@ -813,9 +1208,7 @@ impl<Arg0, Arg1, Arg2, Arg3, Res: Clone> Expectation for ExpectationTimes4<Arg0,
                    mock_name, self.call_match().get_method_name(), self.count, self.number);
         }
         self.count += 1;
-        // nightly: let box (arg0, arg1, arg2, arg3) = CallMatch4::<Arg0, Arg1, Arg2, Arg3, Res>::get_args(call);
-        let (arg0, arg1, arg2, arg3) = *CallMatch4::<Arg0, Arg1, Arg2, Arg3, Res>::get_args(call);
-        let result = (self.action)(arg0, arg1, arg2, arg3);
+        let result = (self.action)(call);
         Box::into_raw(Box::new(result)) as *mut u8
     }
     fn describe(&self) -> String {
@@ -825,11 +1218,12 @@ impl<Arg0, Arg1, Arg2, Arg3, Res: Clone> Expectation for ExpectationTimes4<Arg0,
 }
 
 #[must_use]
-pub struct Expectation4<Arg0, Arg1, Arg2, Arg3, Res> {
-    call_match: CallMatch4<Arg0, Arg1, Arg2, Arg3, Res>,
-    action: Option<Action4<Arg0, Arg1, Arg2, Arg3, Res>>,
+pub struct Expectation4<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Arg3: ?Sized + 'static, Res, Kind0 = ByValue, Kind1 = ByValue, Kind2 = ByValue, Kind3 = ByValue> {
+    call_match: CallMatch4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3>,
+    action: Option<ArgsAction<Res>>,
 }
-impl<Arg0, Arg1, Arg2, Arg3, Res> Expectation for Expectation4<Arg0, Arg1, Arg2, Arg3, Res> {
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Arg3: ?Sized + 'static, Res, Kind0, Kind1, Kind2, Kind3> Expectation for Expectation4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2>, Kind3: for<'a> ArgKind<'a, Arg3> {
     fn call_match(&self) -> &CallMatch {
         &self.call_match
     }
@@ -839,9 +1233,7 @@ impl<Arg0, Arg1, Arg2, Arg3, Res> Expectation for Expectation4<Arg0, Arg1, Arg2,
     fn satisfy(&mut self, call: Call, mock_name: &str) -> *mut u8 {
         match self.action.take() {
             Some(action) => {
-                // nightly: let box (arg0, arg1, arg2, arg3) = CallMatch4::<Arg0, Arg1, Arg2, Arg3, Res>::get_args(call);
-                let (arg0, arg1, arg2, arg3) = *CallMatch4::<Arg0, Arg1, Arg2, Arg3, Res>::get_args(call);
-                let result = action.call(arg0, arg1, arg2, arg3);
+                let result = action.call(call);
                 Box::into_raw(Box::new(result)) as *mut u8
             },
             None => {
@@ -853,43 +1245,79 @@ impl<Arg0, Arg1, Arg2, Arg3, Res> Expectation for Expectation4<Arg0, Arg1, Arg2,
         self.call_match.describe()
     }
 }
-impl<Arg0, Arg1, Arg2, Arg3, Res: 'static> CallMatch4<Arg0, Arg1, Arg2, Arg3, Res> {
-    pub fn and_return(self, result: Res) -> Expectation4<Arg0, Arg1, Arg2, Arg3, Res> {
-        Expectation4 { call_match: self, action: Some(Action4::new(move |_, _, _, _| result)) }
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Arg3: ?Sized + 'static, Res: 'static, Kind0: 'static, Kind1: 'static, Kind2: 'static, Kind3: 'static> CallMatch4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2>, Kind3: for<'a> ArgKind<'a, Arg3> {
+    pub fn and_return(self, result: Res) -> Expectation4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3> {
+        Expectation4 { call_match: self, action: Some(ArgsAction::new(move |_| result)) }
     }
 
-    pub fn and_panic(self, msg: String) -> Expectation4<Arg0, Arg1, Arg2, Arg3, Res> {
-        Expectation4 { call_match: self, action: Some(Action4::new(move |_, _, _, _| panic!(msg))) }
+    pub fn and_panic(self, msg: String) -> Expectation4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3> {
+        Expectation4 { call_match: self, action: Some(ArgsAction::new(move |_| panic!(msg))) }
     }
-
-    pub fn and_call<F>(self, func: F) -> Expectation4<Arg0, Arg1, Arg2, Arg3, Res>
-            where F: FnOnce(Arg0, Arg1, Arg2, Arg3) -> Res + 'static {
-        Expectation4 { call_match: self, action: Some(Action4::new(func)) }
+}
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Arg3: ?Sized + 'static, Res: Clone + 'static, Kind0: 'static, Kind1: 'static, Kind2: 'static, Kind3: 'static> CallMatch4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2>, Kind3: for<'a> ArgKind<'a, Arg3> {
+    pub fn and_return_clone(self, result: Res) -> Reaction4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3> {
+        Reaction4 { call_match: self, action: Box::new(move |_| result.clone()) }
     }
 }
-impl<Arg0, Arg1, Arg2, Arg3, Res: Clone + 'static> CallMatch4<Arg0, Arg1, Arg2, Arg3, Res> {
-    pub fn and_return_clone(self, result: Res) -> Reaction4<Arg0, Arg1, Arg2, Arg3, Res> {
-        Reaction4 { call_match: self, action: Box::new(move |_, _, _, _| result.clone()) }
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Arg3: ?Sized + 'static, Res: Default + 'static, Kind0: 'static, Kind1: 'static, Kind2: 'static, Kind3: 'static> CallMatch4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2>, Kind3: for<'a> ArgKind<'a, Arg3> {
+    pub fn and_return_default(self) -> Reaction4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3> {
+        Reaction4 { call_match: self, action: Box::new(|_| Res::default()) }
     }
+}
 
-    pub fn and_call_clone<F>(self, func: F) -> Reaction4<Arg0, Arg1, Arg2, Arg3, Res>
-            where F: FnMut(Arg0, Arg1, Arg2, Arg3) -> Res + 'static {
-        Reaction4 { call_match: self, action: Box::new(func) }
-    }
+/// Expectation builder returned by `expect_*` methods of mock, see
+/// `ExpectationBuilder0`.
+pub struct ExpectationBuilder4<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Arg3: ?Sized + 'static, Res: 'static, Kind0: 'static = ByValue, Kind1: 'static = ByValue, Kind2: 'static = ByValue, Kind3: 'static = ByValue> {
+    slot: Rc<RefCell<BuilderSlot<Res>>>,
+    arg0: Rc<RefCell<Box<MatchArg<Arg0>>>>,
+    arg1: Rc<RefCell<Box<MatchArg<Arg1>>>>,
+    arg2: Rc<RefCell<Box<MatchArg<Arg2>>>>,
+    arg3: Rc<RefCell<Box<MatchArg<Arg3>>>>,
+    _phantom: PhantomData<(Kind0, Kind1, Kind2, Kind3)>,
 }
-impl<Arg0, Arg1, Arg2, Arg3, Res: Default + 'static> CallMatch4<Arg0, Arg1, Arg2, Arg3, Res> {
-    pub fn and_return_default(self) -> Reaction4<Arg0, Arg1, Arg2, Arg3, Res> {
-        Reaction4 { call_match: self, action: Box::new(|_, _, _, _| Res::default()) }
+impl<Arg0: ?Sized + 'static, Arg1: ?Sized + 'static, Arg2: ?Sized + 'static, Arg3: ?Sized + 'static, Res: 'static, Kind0: 'static, Kind1: 'static, Kind2: 'static, Kind3: 'static> ExpectationBuilder4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3>
+    where Kind0: for<'a> ArgKind<'a, Arg0>, Kind1: for<'a> ArgKind<'a, Arg1>, Kind2: for<'a> ArgKind<'a, Arg2>, Kind3: for<'a> ArgKind<'a, Arg3> {
+    pub fn new(scenario: Rc<RefCell<ScenarioInternals>>, mut call_match: CallMatch4<Arg0, Arg1, Arg2, Arg3, Res, Kind0, Kind1, Kind2, Kind3>) -> Self {
+        let arg0 = share_matcher(&mut call_match.arg0);
+        let arg1 = share_matcher(&mut call_match.arg1);
+        let arg2 = share_matcher(&mut call_match.arg2);
+        let arg3 = share_matcher(&mut call_match.arg3);
+        ExpectationBuilder4 {
+            slot: BuilderSlot::register(&scenario, call_match),
+            arg0: arg0,
+            arg1: arg1,
+            arg2: arg2,
+            arg3: arg3,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets argument matchers, all arguments match anything by default.
+    pub fn with<M0: MatchArg<Arg0> + 'static, M1: MatchArg<Arg1> + 'static, M2: MatchArg<Arg2> + 'static, M3: MatchArg<Arg3> + 'static>(self, arg0: M0, arg1: M1, arg2: M2, arg3: M3) -> Self {
+        *self.arg0.borrow_mut() = Box::new(arg0);
+        *self.arg1.borrow_mut() = Box::new(arg1);
+        *self.arg2.borrow_mut() = Box::new(arg2);
+        *self.arg3.borrow_mut() = Box::new(arg3);
+        self
+    }
+
+    pub fn times(self, number: usize) -> Self {
+        self.slot.borrow_mut().number = number;
+        self
     }
 }
 
+kind_impls!(CallMatch4, Expectation4, Reaction4, ExpectationBuilder4; Arg0 arg0, Arg1 arg1, Arg2 arg2, Arg3 arg3);
 
 /// Argument matcher
 ///
 /// Basically it is predicate telling whether argument
 /// value satisfies to some criteria. However, in case
 /// of mismatch it explains what and why doesn't match.
-pub trait MatchArg<T> {
+pub trait MatchArg<T: ?Sized> {
     fn matches(&self, arg: &T) -> Result<(), String>;
     fn describe(&self) -> String;
 }
@@ -909,6 +1337,22 @@ impl<T: Eq + std::fmt::Debug> MatchArg<T> for T {
     }
 }
 
+/// Matches argument with referenced value using equality,
+/// so `"foo"` may be used to match `str` argument.
+impl<'a, T: ?Sized + Eq + std::fmt::Debug> MatchArg<T> for &'a T {
+    fn matches(&self, arg: &T) -> Result<(), String> {
+        if *self == arg {
+            Ok(())
+        } else {
+            Err(format!("{:?} is not equal to {:?}", arg, self))
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
 #[derive(Default)]
 pub struct Sequence {
     expectations: Vec<Box<Expectation>>,
@@ -951,6 +1395,12 @@ impl Expectation for Sequence {
 pub trait Mock {
     fn new(id: usize, scenario_int: Rc<RefCell<ScenarioInternals>>) -> Self;
     fn mocked_class_name() -> &'static str;
+    /// Name used to generate mock names, by default it is the same as
+    /// `mocked_class_name`. Mocks of generic traits include type parameters
+    /// into it, like `Handler<u32>`.
+    fn mocked_type_name() -> String {
+        Self::mocked_class_name().to_owned()
+    }
 }
 
 pub trait Mocked {
@@ -964,29 +1414,69 @@ pub struct ScenarioInternals {
     mock_names: HashMap<usize, Rc<String>>,
     /// Set of used mock names used to quicly check for conflicts.
     allocated_names: HashSet<Rc<String>>,
+
+    /// Mapping from mocked class name to ID of pseudo-mock used
+    /// for static methods of this class.
+    static_mock_ids: HashMap<String, usize>,
+
+    next_mock_id: usize,
 }
 
 pub struct Scenario {
     internals: Rc<RefCell<ScenarioInternals>>,
-    next_mock_id: usize,
+}
+
+thread_local! {
+    /// Stack of scenarios alive in current thread. Static methods of mocks
+    /// have no `self` to get scenario from, so they use the most recently
+    /// created one.
+    static SCENARIOS: RefCell<Vec<Weak<RefCell<ScenarioInternals>>>> = RefCell::new(Vec::new());
+}
+
+/// Returns scenario which static mock methods are bound to, i.e. the most
+/// recently created scenario which is still alive in current thread.
+pub fn current_scenario() -> Rc<RefCell<ScenarioInternals>> {
+    SCENARIOS.with(|scenarios| {
+        scenarios.borrow().last().and_then(|s| s.upgrade())
+    }).expect("static mock method is called, but there is no scenario in current thread")
+}
+
+/// Returns ID of pseudo-mock which static methods of given class are
+/// attributed to in current scenario, see `get_static_mock_id`.
+pub fn static_mock_id(class_name: &str) -> usize {
+    // Both are bound to variables, so that `RefMut` is dropped before
+    // scenario it borrows, which isn't so for tail expression temporaries.
+    let scenario = current_scenario();
+    let mut scenario_int = scenario.borrow_mut();
+    scenario_int.get_static_mock_id(class_name)
+}
+
+/// Returns call matcher factory for free function marked with `#[mockable]`,
+/// it is used like `scenario.expect(mock_fn!(read_config).call(ANY).and_return(config))`.
+#[macro_export]
+macro_rules! mock_fn {
+    ($func:ty) => { <$func>::mock_fn() };
 }
 
 impl Scenario {
     pub fn new() -> Self {
-        Scenario {
-            internals: Rc::new(RefCell::new(ScenarioInternals {
-                expectations: Vec::new(),
+        let internals = Rc::new(RefCell::new(ScenarioInternals {
+            expectations: Vec::new(),
+
+            mock_names: HashMap::new(),
+            allocated_names: HashSet::new(),
+
+            static_mock_ids: HashMap::new(),
 
-                mock_names: HashMap::new(),
-                allocated_names: HashSet::new(),
-            })),
             next_mock_id: 0,
-        }
+        }));
+        SCENARIOS.with(|scenarios| scenarios.borrow_mut().push(Rc::downgrade(&internals)));
+        Scenario { internals: internals }
     }
 
     pub fn create_mock<T: Mock>(&mut self) -> T {
         let mock_id = self.get_next_mock_id();
-        self.generate_name_for_class(mock_id, T::mocked_class_name());
+        self.generate_name_for_class(mock_id, &T::mocked_type_name());
         T::new(mock_id, self.internals.clone())
     }
 
@@ -1007,9 +1497,7 @@ impl Scenario {
     }
 
     fn get_next_mock_id(&mut self) -> usize {
-        let id = self.next_mock_id;
-        self.next_mock_id += 1;
-        id
+        self.internals.borrow_mut().get_next_mock_id()
     }
 
     pub fn expect<C: Expectation + 'static>(&mut self, call: C) {
@@ -1031,6 +1519,9 @@ impl Scenario {
             for expectation in active_expectations {
                 let mock_name = mock_names.get(&expectation.call_match().get_mock_id()).unwrap();
                 s.push_str(&format!("`{}.{}`\n", mock_name, expectation.describe()));
+                for (subject, problem) in expectation.mismatches() {
+                    s.push_str(&format!("    {}: {}\n", subject, problem));
+                }
             }
             panic!(s);
         }
@@ -1038,6 +1529,9 @@ impl Scenario {
 
     fn register_name(&mut self, mock_id: usize, name: String) {
         let mut int = self.internals.borrow_mut();
+        if name.starts_with(STATIC_MOCK_NAME_PREFIX) {
+            panic!("Mock name {} is reserved for static methods", name);
+        }
         if int.allocated_names.contains(&name) {
             panic!("Mock name {} already used", name);
         }
@@ -1066,6 +1560,14 @@ impl Default for Scenario {
 
 impl Drop for Scenario {
     fn drop(&mut self) {
+        let this: *const RefCell<ScenarioInternals> = &*self.internals;
+        SCENARIOS.with(|scenarios| {
+            scenarios.borrow_mut().retain(|s| match s.upgrade() {
+                Some(rc) => &*rc as *const RefCell<ScenarioInternals> != this,
+                None => false,
+            });
+        });
+
         // Test is already failed, so it isn't necessary to check remaining
         // expectations. And if we do, then panic-during-drop will cause
         // test to fail with uncomprehensive message like:
@@ -1078,10 +1580,108 @@ impl Drop for Scenario {
     }
 }
 
+/// Identifies concrete instantiation of generic method's type parameters.
+///
+/// Call of generic method may only be inspected by call matcher created
+/// for the same type parameters, because arguments types depend on them.
+///
+/// `std::any::TypeId` of type parameters is compared. Type parameters of
+/// methods in mocked trait implementation can't get `'static` bound, so
+/// `TypeId` is obtained with lifetimes ignored, see `type_id_of`. Thus
+/// type parameters differing in lifetimes only, like `&'a str` and
+/// `&'static str`, are equal.
+#[derive(Clone, Copy, Debug)]
+pub struct TypeParams {
+    id: TypeId,
+    name: &'static str,
+}
+impl TypeParams {
+    /// Type parameters are passed as tuple, `()` is used for
+    /// non-generic methods.
+    pub fn of<T: ?Sized>() -> Self {
+        TypeParams { id: type_id_of::<T>(), name: type_name::<T>() }
+    }
+
+    pub fn name(&self) -> &'static str { self.name }
+}
+impl PartialEq for TypeParams {
+    fn eq(&self, other: &Self) -> bool { self.id == other.id }
+}
+impl Eq for TypeParams {}
+
+trait NonStaticAny {
+    fn get_type_id(&self) -> TypeId where Self: 'static;
+}
+impl<T: ?Sized> NonStaticAny for PhantomData<T> {
+    fn get_type_id(&self) -> TypeId where Self: 'static { TypeId::of::<T>() }
+}
+
+/// Returns `TypeId` of `T` with all lifetimes replaced by `'static`.
+///
+/// `TypeId` doesn't depend on lifetimes, so pretending that type is
+/// `'static` just to get its ID is harmless: neither value of `T` nor
+/// `PhantomData` is accessed and returned ID is never used for
+/// downcasting. The price is that types differing in lifetimes only
+/// get the same ID.
+fn type_id_of<T: ?Sized>() -> TypeId {
+    let phantom = PhantomData::<T>;
+    let any: &NonStaticAny = &phantom;
+    let any: &(NonStaticAny + 'static) = unsafe { std::mem::transmute(any) };
+    any.get_type_id()
+}
+
+/// Returns name of type `T` for use in error messages.
+///
+/// Type names are available on nightly Rust only, `_` is returned otherwise.
+#[cfg(feature="nightly")]
+pub fn type_name<T: ?Sized>() -> &'static str {
+    unsafe { std::intrinsics::type_name::<T>() }
+}
+#[cfg(not(feature="nightly"))]
+pub fn type_name<T: ?Sized>() -> &'static str {
+    "_"
+}
+
+/// Aborts process, it is used by mocks of `extern "C"` callbacks,
+/// which must not unwind into C code.
+///
+/// `std::process::abort` isn't stable yet, so on stable Rust process
+/// is aborted by panic during unwinding.
+#[cfg(feature="nightly")]
+pub fn abort() -> ! {
+    unsafe { std::intrinsics::abort() }
+}
+#[cfg(not(feature="nightly"))]
+pub fn abort() -> ! {
+    struct PanicOnDrop;
+    impl Drop for PanicOnDrop {
+        fn drop(&mut self) {
+            panic!("aborting");
+        }
+    }
+    let _guard = PanicOnDrop;
+    panic!("aborting");
+}
+
+/// Returns mock type ID for key describing mocked method, it is
+/// 64-bit FNV-1a hash of key.
+///
+/// Generated mocks use key made of crate name, module path, mock name
+/// and method signature, ready-made mocks from `std_mocks` use mock
+/// and method names. Both compute ID with this function, so IDs don't
+/// depend on expansion order and are the same for separate `expand` runs.
+pub fn mock_type_id(key: &str) -> usize {
+    key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    }) as usize
+}
+
 pub struct Call {
     pub mock_id: usize,
     pub mock_type_id: usize,
     pub method_name: &'static str,
+    pub type_params: TypeParams,
+    pub arg_names: &'static [&'static str],
     pub args_ptr: *const u8,
     pub destroy: fn(*const u8),
     pub format_args: fn(*const u8) -> String,
@@ -1134,13 +1734,45 @@ macro_rules! colored {
     (white_bold: $s:expr) => ( concat!("\x1b[1;97m", $s, "\x1b[0m") );
 }
 
+/// Prefix of names of pseudo-mocks used for static methods.
+const STATIC_MOCK_NAME_PREFIX: &'static str = "<static ";
+
 impl ScenarioInternals {
+    fn get_next_mock_id(&mut self) -> usize {
+        let id = self.next_mock_id;
+        self.next_mock_id += 1;
+        id
+    }
+
+    /// Returns name of mock with given ID.
+    pub fn get_mock_name(&self, mock_id: usize) -> &str {
+        self.mock_names.get(&mock_id).expect("unknown mock ID")
+    }
+
+    /// Returns ID used for calls of static methods of given mocked class.
+    ///
+    /// Static methods have no mock object to be called on, so all their
+    /// calls are attributed to single pseudo-mock named like
+    /// `<static Connection>`. Such names are reserved, so they don't
+    /// collide with names given by user.
+    pub fn get_static_mock_id(&mut self, class_name: &str) -> usize {
+        if let Some(&id) = self.static_mock_ids.get(class_name) {
+            return id;
+        }
+        let id = self.get_next_mock_id();
+        let name_rc = Rc::new(format!("{}{}>", STATIC_MOCK_NAME_PREFIX, class_name));
+        self.mock_names.insert(id, name_rc.clone());
+        self.allocated_names.insert(name_rc);
+        self.static_mock_ids.insert(class_name.to_owned(), id);
+        id
+    }
+
     /// Verify call performed on mock object
     pub fn verify(&mut self, call: Call) -> *mut u8 {
 
         for expectation in self.expectations.iter_mut().rev() {
             if expectation.call_match().matches(&call) {
-                let mock_name = self.mock_names.get(&call.mock_id).unwrap();
+                let mock_name = &self.mock_names[&call.mock_id];
                 return expectation.satisfy(call, mock_name);
             }
         }
@@ -1148,7 +1780,7 @@ impl ScenarioInternals {
         // No expectations exactly matching call are found. However this may be
         // because of unexpected argument values. So check active expectations
         // with matching target (i.e. mock and method) and validate arguments.
-        let mock_name = self.mock_names.get(&call.mock_id).unwrap();
+        let mock_name = &self.mock_names[&call.mock_id];
 
         let mut msg = String::new();
         msg.write_str("\n\n").unwrap();
@@ -1175,9 +1807,15 @@ impl ScenarioInternals {
                 write!(&mut msg, "\n  expectation `{}.{}`:\n", mock_name, expectation.describe()).unwrap();
                 for (index, res) in expectation.call_match().validate(&call).iter().enumerate() {
                     match *res {
-                        Err(ref err) =>
-                            write!(&mut msg, concat!("    arg #{}: ", colored!(bold: "{}"), "\n"),
-                                   index, err).unwrap(),
+                        Err(ref err) => {
+                            let label = match call.arg_names.get(index) {
+                                Some(name) if !name.is_empty() => format!("arg `{}`", name),
+                                Some(_) => format!("arg #{}", index),
+                                None => "args".to_owned(),
+                            };
+                            write!(&mut msg, concat!("    {}: ", colored!(bold: "{}"), "\n"),
+                                   label, err).unwrap()
+                        },
                         Ok(()) => ()
                     }
                 }
@@ -1189,6 +1827,30 @@ impl ScenarioInternals {
                    mock_name, call.method_name).unwrap();
         }
 
+        // Expectations for another instantiation of generic method can't
+        // validate arguments, so just tell about type parameters mismatch.
+        let mut type_params_first_match = true;
+        for expectation in self.expectations.iter().rev() {
+            let call_match = expectation.call_match();
+            if !expectation.is_satisfied() &&
+               call_match.get_mock_id() == call.mock_id &&
+               call_match.get_method_name() == call.method_name &&
+               call_match.get_type_params() != call.type_params {
+
+                if type_params_first_match {
+                    write!(&mut msg, concat!(colored!(green: "note: "),
+                                             "there are active expectations for {}.{} with other type parameters\n"),
+                           mock_name, call.method_name).unwrap();
+                    type_params_first_match = false;
+                }
+
+                write!(&mut msg, concat!("\n  expectation `{}.{}`: type parameters are ",
+                                         colored!(bold: "{}"), ", but called with ", colored!(bold: "{}"), "\n"),
+                       mock_name, expectation.describe(),
+                       call_match.get_type_params().name(), call.type_params.name()).unwrap();
+            }
+        }
+
         let mut method_first_match = true;
         for expectation in self.expectations.iter().rev() {
             if !expectation.is_satisfied() &&
@@ -1203,7 +1865,7 @@ impl ScenarioInternals {
                 }
 
                 let other_mock_id = &expectation.call_match().get_mock_id();
-                let other_mock_name = self.mock_names.get(other_mock_id).unwrap();
+                let other_mock_name = &self.mock_names[other_mock_id];
                 write!(&mut msg, concat!("\n  expectation `", colored!(bold: "{}"), ".{}`\n"),
                        other_mock_name, expectation.describe()).unwrap();
             }
@@ -3,12 +3,12 @@ use std::marker::PhantomData;
 use std::fmt::Debug;
 use MatchArg;
 
-pub trait MatchArgExt<T: Debug, M: MatchArg<T>> {
+pub trait MatchArgExt<T: ?Sized + Debug, M: MatchArg<T>> {
     fn with_custom_msg<F: Fn(&T) -> String>(self, msg_fn: F) -> WithMessageFn<T, M, F>;
     fn with_description_fn<F: Fn() -> String>(self, description_fn: F) -> WithDescriptionFn<T, M, F>;
 }
 
-impl<T: Debug, M: MatchArg<T>> MatchArgExt<T, M> for M {
+impl<T: ?Sized + Debug, M: MatchArg<T>> MatchArgExt<T, M> for M {
     fn with_custom_msg<F: Fn(&T) -> String>(self, msg_fn: F) -> WithMessageFn<T, M, F> {
         WithMessageFn::new(self, msg_fn)
     }
@@ -18,12 +18,12 @@ impl<T: Debug, M: MatchArg<T>> MatchArgExt<T, M> for M {
 }
 
 
-pub struct WithDescriptionFn<T: Debug, M: MatchArg<T>, F: Fn() -> String> {
+pub struct WithDescriptionFn<T: ?Sized + Debug, M: MatchArg<T>, F: Fn() -> String> {
     matcher: M,
     description_fn: F,
     _phantom: PhantomData<T>,
 }
-impl<T: Debug, M: MatchArg<T>, F: Fn() -> String> WithDescriptionFn<T, M, F> {
+impl<T: ?Sized + Debug, M: MatchArg<T>, F: Fn() -> String> WithDescriptionFn<T, M, F> {
     pub fn new(matcher: M, description_fn: F) -> Self {
         WithDescriptionFn {
             matcher: matcher,
@@ -36,7 +36,7 @@ impl<T: Debug, M: MatchArg<T>, F: Fn() -> String> WithDescriptionFn<T, M, F> {
         fun()
     }
 }
-impl<T: Debug, M: MatchArg<T>, F: Fn() -> String> MatchArg<T> for WithDescriptionFn<T, M, F> {
+impl<T: ?Sized + Debug, M: MatchArg<T>, F: Fn() -> String> MatchArg<T> for WithDescriptionFn<T, M, F> {
     fn matches(&self, arg: &T) -> Result<(), String> {
         self.matcher.matches(arg)
     }
@@ -46,12 +46,12 @@ impl<T: Debug, M: MatchArg<T>, F: Fn() -> String> MatchArg<T> for WithDescriptio
 }
 
 
-pub struct WithMessageFn<T: Debug, M: MatchArg<T>, F: Fn(&T) -> String> {
+pub struct WithMessageFn<T: ?Sized + Debug, M: MatchArg<T>, F: Fn(&T) -> String> {
     matcher: M,
     msg_fn: F,
     _phantom: PhantomData<T>,
 }
-impl<T: Debug, M: MatchArg<T>, F: Fn(&T) -> String> WithMessageFn<T, M, F> {
+impl<T: ?Sized + Debug, M: MatchArg<T>, F: Fn(&T) -> String> WithMessageFn<T, M, F> {
     pub fn new(matcher: M, msg_fn: F) -> Self {
         WithMessageFn {
             matcher: matcher,
@@ -64,7 +64,7 @@ impl<T: Debug, M: MatchArg<T>, F: Fn(&T) -> String> WithMessageFn<T, M, F> {
         fun(arg)
     }
 }
-impl<T: Debug, M: MatchArg<T>, F: Fn(&T) -> String> MatchArg<T> for WithMessageFn<T, M, F> {
+impl<T: ?Sized + Debug, M: MatchArg<T>, F: Fn(&T) -> String> MatchArg<T> for WithMessageFn<T, M, F> {
     fn matches(&self, arg: &T) -> Result<(), String> {
         match self.matcher.matches(arg) {
             Ok(()) => Ok(()),
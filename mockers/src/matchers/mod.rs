@@ -22,7 +22,7 @@ impl ToString for MatchAny {
         "_".to_owned()
     }
 }
-impl<T> MatchArg<T> for MatchAny {
+impl<T: ?Sized> MatchArg<T> for MatchAny {
     fn matches(&self, _: &T) -> Result<(), String> {
         Ok(())
     }
@@ -148,8 +148,8 @@ pub fn in_range<T: Ord + Debug, R: RangeArgument<T>>(range: R) -> RangeMatchArg<
     RangeMatchArg { range: range, _phantom: PhantomData }
 }
 
-pub struct NotMatchArg<T: Debug, M: MatchArg<T>>(M, PhantomData<T>);
-impl<T: Debug, M: MatchArg<T>> MatchArg<T> for NotMatchArg<T, M> {
+pub struct NotMatchArg<T: ?Sized + Debug, M: MatchArg<T>>(M, PhantomData<T>);
+impl<T: ?Sized + Debug, M: MatchArg<T>> MatchArg<T> for NotMatchArg<T, M> {
     fn matches(&self, arg: &T) -> Result<(), String> {
         match self.0.matches(arg) {
             Err(_) => Ok(()),
@@ -161,15 +161,15 @@ impl<T: Debug, M: MatchArg<T>> MatchArg<T> for NotMatchArg<T, M> {
         format!("lt({:?})", self.0.describe())
     }
 }
-pub fn not<T: Debug, M: MatchArg<T>>(matcher: M) -> NotMatchArg<T, M> {
+pub fn not<T: ?Sized + Debug, M: MatchArg<T>>(matcher: M) -> NotMatchArg<T, M> {
     NotMatchArg(matcher, PhantomData)
 }
 
 
-pub struct AndMatchArg<T: Debug,
+pub struct AndMatchArg<T: ?Sized + Debug,
                        M0: MatchArg<T>,
                        M1: MatchArg<T>>(M0, M1, PhantomData<T>);
-impl<T: Debug, M0: MatchArg<T>, M1: MatchArg<T>> MatchArg<T> for AndMatchArg<T, M0, M1> {
+impl<T: ?Sized + Debug, M0: MatchArg<T>, M1: MatchArg<T>> MatchArg<T> for AndMatchArg<T, M0, M1> {
     fn matches(&self, arg: &T) -> Result<(), String> {
         match self.0.matches(arg) {
             err @ Err(_) => err,
@@ -184,15 +184,15 @@ impl<T: Debug, M0: MatchArg<T>, M1: MatchArg<T>> MatchArg<T> for AndMatchArg<T,
         format!("and({}, {})", self.0.describe(), self.1.describe())
     }
 }
-pub fn and<T: Debug, M0: MatchArg<T>, M1: MatchArg<T>>(matcher0: M0, matcher1: M1) -> AndMatchArg<T, M0, M1> {
+pub fn and<T: ?Sized + Debug, M0: MatchArg<T>, M1: MatchArg<T>>(matcher0: M0, matcher1: M1) -> AndMatchArg<T, M0, M1> {
     AndMatchArg(matcher0, matcher1, PhantomData)
 }
 
 
-pub struct OrMatchArg<T: Debug,
+pub struct OrMatchArg<T: ?Sized + Debug,
                       M0: MatchArg<T>,
                       M1: MatchArg<T>>(M0, M1, PhantomData<T>);
-impl<T: Debug, M0: MatchArg<T>, M1: MatchArg<T>> MatchArg<T> for OrMatchArg<T, M0, M1> {
+impl<T: ?Sized + Debug, M0: MatchArg<T>, M1: MatchArg<T>> MatchArg<T> for OrMatchArg<T, M0, M1> {
     fn matches(&self, arg: &T) -> Result<(), String> {
         match self.0.matches(arg) {
             Ok(()) => Ok(()),
@@ -207,16 +207,16 @@ impl<T: Debug, M0: MatchArg<T>, M1: MatchArg<T>> MatchArg<T> for OrMatchArg<T, M
         format!("or({}, {})", self.0.describe(), self.1.describe())
     }
 }
-pub fn or<T: Debug, M0: MatchArg<T>, M1: MatchArg<T>>(matcher0: M0, matcher1: M1) -> OrMatchArg<T, M0, M1> {
+pub fn or<T: ?Sized + Debug, M0: MatchArg<T>, M1: MatchArg<T>>(matcher0: M0, matcher1: M1) -> OrMatchArg<T, M0, M1> {
     OrMatchArg(matcher0, matcher1, PhantomData)
 }
 
 
-pub struct FnMatchArg<T, F: Fn(&T) -> Result<(), String>> {
+pub struct FnMatchArg<T: ?Sized, F: Fn(&T) -> Result<(), String>> {
     func: F,
     _phantom: PhantomData<T>,
 }
-impl<T, F: Fn(&T) -> Result<(), String>> FnMatchArg<T, F> {
+impl<T: ?Sized, F: Fn(&T) -> Result<(), String>> FnMatchArg<T, F> {
     pub fn new(func: F) -> Self {
         FnMatchArg {
             func: func,
@@ -224,7 +224,7 @@ impl<T, F: Fn(&T) -> Result<(), String>> FnMatchArg<T, F> {
         }
     }
 }
-impl<T, F: Fn(&T) -> Result<(), String>> MatchArg<T> for FnMatchArg<T, F> {
+impl<T: ?Sized, F: Fn(&T) -> Result<(), String>> MatchArg<T> for FnMatchArg<T, F> {
     fn matches(&self, arg: &T) -> Result<(), String> {
         let func = &self.func;
         func(arg)
@@ -235,11 +235,11 @@ impl<T, F: Fn(&T) -> Result<(), String>> MatchArg<T> for FnMatchArg<T, F> {
 }
 
 
-pub struct BoolFnMatchArg<T, F: Fn(&T) -> bool> {
+pub struct BoolFnMatchArg<T: ?Sized, F: Fn(&T) -> bool> {
     func: F,
     _phantom: PhantomData<T>,
 }
-impl<T, F: Fn(&T) -> bool> BoolFnMatchArg<T, F> {
+impl<T: ?Sized, F: Fn(&T) -> bool> BoolFnMatchArg<T, F> {
     pub fn new(func: F) -> Self {
         BoolFnMatchArg {
             func: func,
@@ -247,7 +247,7 @@ impl<T, F: Fn(&T) -> bool> BoolFnMatchArg<T, F> {
         }
     }
 }
-impl<T, F: Fn(&T) -> bool> MatchArg<T> for BoolFnMatchArg<T, F> {
+impl<T: ?Sized, F: Fn(&T) -> bool> MatchArg<T> for BoolFnMatchArg<T, F> {
     fn matches(&self, arg: &T) -> Result<(), String> {
         let func = &self.func;
         if func(arg) {
@@ -260,7 +260,7 @@ impl<T, F: Fn(&T) -> bool> MatchArg<T> for BoolFnMatchArg<T, F> {
         "<custom function>".to_owned()
     }
 }
-pub fn check<T, F: Fn(&T) -> bool>(f: F) -> BoolFnMatchArg<T, F> {
+pub fn check<T: ?Sized, F: Fn(&T) -> bool>(f: F) -> BoolFnMatchArg<T, F> {
     BoolFnMatchArg { func: f, _phantom: PhantomData }
 }
 
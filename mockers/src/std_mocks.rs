@@ -0,0 +1,359 @@
+//! Ready-made mocks for commonly mocked traits of standard library.
+//!
+//! Mocks are written by hand, but their `*_call` methods are the same
+//! as generated by `mock!`, so expectations are set like for any other mock:
+//!
+//! ```ignore
+//! use mockers::std_mocks::ReadMock;
+//!
+//! let reader = scenario.create_mock_for::<Read>();
+//! scenario.expect(reader.read_call(ANY).and_call(|buf: &mut [u8]| { buf[0] = 42; Ok(1) }));
+//! ```
+//!
+//! Only required methods of traits are mocked, provided ones keep their
+//! default implementations, so `read_exact` or `write_all` calls `read`
+//! or `write` mock methods.
+//!
+//! Unlike generated mocks they have no handles, `expect_*` builders,
+//! `*_call_with` methods and argument structs.
+//!
+//! Callbacks have no trait which may be implemented on stable Rust, so
+//! `FnMock0`, `FnMock1` and `FnMock2` mocks give boxed closures with
+//! `as_fn` method.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, Read, Write, Seek, SeekFrom, BufRead};
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use super::{Mock, Mocked, ScenarioInternals, Call, CallMatch0, CallMatch1, CallMatch2,
+            MatchArg, ByRef, ByMutRef, TypeParams, type_name, mock_type_id};
+
+/// Declares mock struct and implements `Mock`, `Debug`, `PartialEq`
+/// and `Eq` for it like `mock!` does.
+macro_rules! mock_struct {
+    // Type parameters are passed with leading commas, because macro parser
+    // can't choose between `ident` and `>` after `<` of empty list.
+    (@impl $(#[$attr:meta])* struct $mock:ident [$(, $param:ident)*], $class_name:expr $(, $type_name:expr)*) => {
+        $(#[$attr])*
+        pub struct $mock<$($param),*> {
+            scenario: Rc<RefCell<ScenarioInternals>>,
+            mock_id: usize,
+            _phantom: PhantomData<($(*const $param,)*)>,
+        }
+        impl<$($param: 'static),*> Mock for $mock<$($param),*> {
+            fn new(id: usize, scenario_int: Rc<RefCell<ScenarioInternals>>) -> Self {
+                $mock {
+                    scenario: scenario_int,
+                    mock_id: id,
+                    _phantom: PhantomData,
+                }
+            }
+
+            fn mocked_class_name() -> &'static str {
+                $class_name
+            }
+
+            $(
+            fn mocked_type_name() -> String {
+                $type_name
+            }
+            )*
+        }
+        impl<$($param),*> fmt::Debug for $mock<$($param),*> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                // Mock may be printed by action while scenario is borrowed.
+                match self.scenario.try_borrow() {
+                    Ok(scenario) => f.write_str(scenario.get_mock_name(self.mock_id)),
+                    Err(_) => write!(f, "#{}", self.mock_id),
+                }
+            }
+        }
+        impl<$($param),*> PartialEq for $mock<$($param),*> {
+            fn eq(&self, other: &Self) -> bool {
+                self.mock_id == other.mock_id &&
+                    &*self.scenario as *const _ == &*other.scenario as *const _
+            }
+        }
+        impl<$($param),*> Eq for $mock<$($param),*> {}
+    };
+    ($(#[$attr:meta])* struct $mock:ident<$($param:ident),+>, $($rest:tt)*) => {
+        mock_struct!(@impl $(#[$attr])* struct $mock [$(, $param)+], $($rest)*);
+    };
+    ($(#[$attr:meta])* struct $mock:ident, $($rest:tt)*) => {
+        mock_struct!(@impl $(#[$attr])* struct $mock [], $($rest)*);
+    };
+}
+
+/// Formats tuple of call arguments as comma-separated list.
+trait FormatArgs {
+    fn format_args(&self) -> String;
+}
+impl FormatArgs for () {
+    fn format_args(&self) -> String { String::new() }
+}
+impl<A0: fmt::Debug> FormatArgs for (A0,) {
+    fn format_args(&self) -> String { format!("{:?}", self.0) }
+}
+impl<A0: fmt::Debug, A1: fmt::Debug> FormatArgs for (A0, A1) {
+    fn format_args(&self) -> String { format!("{:?}, {:?}", self.0, self.1) }
+}
+
+fn format_debug<Args: FormatArgs>(args_ptr: *const u8) -> String {
+    let args: &Args = unsafe { &*(args_ptr as *const Args) };
+    args.format_args()
+}
+
+// Arguments of callbacks aren't required to implement `Debug`.
+fn format_hidden_0(_: *const u8) -> String { String::new() }
+fn format_hidden_1(_: *const u8) -> String { "_".to_owned() }
+fn format_hidden_2(_: *const u8) -> String { "_, _".to_owned() }
+
+fn destroy<Args>(args_ptr: *const u8) {
+    unsafe { Box::from_raw(args_ptr as *mut Args) };
+}
+
+/// Passes call to scenario and returns result of matched expectation,
+/// it is what body of each generated mocked method does.
+#[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
+fn call<Args, Res>(scenario: &Rc<RefCell<ScenarioInternals>>, mock_id: usize,
+                   mock_type_id: usize, method_name: &'static str, type_params: TypeParams,
+                   arg_names: &'static [&'static str], args: Args,
+                   format_args: fn(*const u8) -> String) -> Res {
+    let args_ptr = Box::into_raw(Box::new(args)) as *const u8;
+    let call = Call { mock_id: mock_id,
+                      mock_type_id: mock_type_id,
+                      method_name: method_name,
+                      type_params: type_params,
+                      arg_names: arg_names,
+                      args_ptr: args_ptr,
+                      destroy: destroy::<Args>,
+                      format_args: format_args };
+    let result_ptr: *mut u8 = scenario.borrow_mut().verify(call);
+    let result: Box<Res> = unsafe { Box::from_raw(result_ptr as *mut Res) };
+    *result
+}
+
+/// Mock type ID of mocked method. It is derived from mock and method names
+/// by `mock_type_id` which generated mocks use too, so methods of different
+/// mocks, like `read` of `ReadMock` and `BufReadMock`, don't match calls
+/// of each other.
+fn method_type_id(mock: &str, method: &str) -> usize {
+    mock_type_id(&format!("mockers::std_mocks::{}::{}", mock, method))
+}
+
+const NO_ARGS: &'static [&'static str] = &[];
+const BUF_ARGS: &'static [&'static str] = &["buf"];
+const POS_ARGS: &'static [&'static str] = &["pos"];
+const AMT_ARGS: &'static [&'static str] = &["amt"];
+// Callback arguments have no names.
+const CALLBACK1_ARGS: &'static [&'static str] = &[""];
+const CALLBACK2_ARGS: &'static [&'static str] = &["", ""];
+
+mock_struct!(
+    /// Mock for `std::io::Read`.
+    struct ReadMock, "Read");
+
+impl ReadMock {
+    pub fn read_call<Arg0Match: MatchArg<[u8]> + 'static>(&self, buf: Arg0Match)
+            -> CallMatch1<[u8], io::Result<usize>, ByMutRef> {
+        CallMatch1::new(self.mock_id, method_type_id("ReadMock", "read"), "read",
+                        TypeParams::of::<()>(), BUF_ARGS, Box::new(buf))
+    }
+}
+impl Read for ReadMock {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        call(&self.scenario, self.mock_id, method_type_id("ReadMock", "read"), "read",
+             TypeParams::of::<()>(), BUF_ARGS, (buf,), format_debug::<(&mut [u8],)>)
+    }
+}
+impl Mocked for &'static Read {
+    type MockImpl = ReadMock;
+}
+
+mock_struct!(
+    /// Mock for `std::io::Write`.
+    struct WriteMock, "Write");
+
+impl WriteMock {
+    pub fn write_call<Arg0Match: MatchArg<[u8]> + 'static>(&self, buf: Arg0Match)
+            -> CallMatch1<[u8], io::Result<usize>, ByRef> {
+        CallMatch1::new(self.mock_id, method_type_id("WriteMock", "write"), "write",
+                        TypeParams::of::<()>(), BUF_ARGS, Box::new(buf))
+    }
+
+    pub fn flush_call(&self) -> CallMatch0<io::Result<()>> {
+        CallMatch0::new(self.mock_id, method_type_id("WriteMock", "flush"), "flush",
+                        TypeParams::of::<()>(), NO_ARGS)
+    }
+}
+impl Write for WriteMock {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        call(&self.scenario, self.mock_id, method_type_id("WriteMock", "write"), "write",
+             TypeParams::of::<()>(), BUF_ARGS, (buf,), format_debug::<(&[u8],)>)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        call(&self.scenario, self.mock_id, method_type_id("WriteMock", "flush"), "flush",
+             TypeParams::of::<()>(), NO_ARGS, (), format_debug::<()>)
+    }
+}
+impl Mocked for &'static Write {
+    type MockImpl = WriteMock;
+}
+
+mock_struct!(
+    /// Mock for `std::io::Seek`.
+    struct SeekMock, "Seek");
+
+impl SeekMock {
+    pub fn seek_call<Arg0Match: MatchArg<SeekFrom> + 'static>(&self, pos: Arg0Match)
+            -> CallMatch1<SeekFrom, io::Result<u64>> {
+        CallMatch1::new(self.mock_id, method_type_id("SeekMock", "seek"), "seek",
+                        TypeParams::of::<()>(), POS_ARGS, Box::new(pos))
+    }
+}
+impl Seek for SeekMock {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        call(&self.scenario, self.mock_id, method_type_id("SeekMock", "seek"), "seek",
+             TypeParams::of::<()>(), POS_ARGS, (pos,), format_debug::<(SeekFrom,)>)
+    }
+}
+impl Mocked for &'static Seek {
+    type MockImpl = SeekMock;
+}
+
+mock_struct!(
+    /// Mock for `std::io::BufRead`, it mocks `Read` supertrait too.
+    ///
+    /// Buffer returned from `fill_buf` must be `'static`.
+    struct BufReadMock, "BufRead");
+
+impl BufReadMock {
+    pub fn read_call<Arg0Match: MatchArg<[u8]> + 'static>(&self, buf: Arg0Match)
+            -> CallMatch1<[u8], io::Result<usize>, ByMutRef> {
+        CallMatch1::new(self.mock_id, method_type_id("BufReadMock", "read"), "read",
+                        TypeParams::of::<()>(), BUF_ARGS, Box::new(buf))
+    }
+
+    pub fn fill_buf_call(&self) -> CallMatch0<io::Result<&'static [u8]>> {
+        CallMatch0::new(self.mock_id, method_type_id("BufReadMock", "fill_buf"), "fill_buf",
+                        TypeParams::of::<()>(), NO_ARGS)
+    }
+
+    pub fn consume_call<Arg0Match: MatchArg<usize> + 'static>(&self, amt: Arg0Match)
+            -> CallMatch1<usize, ()> {
+        CallMatch1::new(self.mock_id, method_type_id("BufReadMock", "consume"), "consume",
+                        TypeParams::of::<()>(), AMT_ARGS, Box::new(amt))
+    }
+}
+impl Read for BufReadMock {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        call(&self.scenario, self.mock_id, method_type_id("BufReadMock", "read"), "read",
+             TypeParams::of::<()>(), BUF_ARGS, (buf,), format_debug::<(&mut [u8],)>)
+    }
+}
+impl BufRead for BufReadMock {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        call::<(), io::Result<&'static [u8]>>(&self.scenario, self.mock_id,
+                                              method_type_id("BufReadMock", "fill_buf"), "fill_buf",
+                                              TypeParams::of::<()>(), NO_ARGS, (), format_debug::<()>)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        call(&self.scenario, self.mock_id, method_type_id("BufReadMock", "consume"), "consume",
+             TypeParams::of::<()>(), AMT_ARGS, (amt,), format_debug::<(usize,)>)
+    }
+}
+impl Mocked for &'static BufRead {
+    type MockImpl = BufReadMock;
+}
+
+mock_struct!(
+    /// Mock for `Iterator<Item=T>`.
+    struct IteratorMock<T>, "Iterator", format!("Iterator<{}>", type_name::<T>()));
+
+impl<T: 'static> IteratorMock<T> {
+    pub fn next_call(&self) -> CallMatch0<Option<T>> {
+        CallMatch0::new(self.mock_id, method_type_id("IteratorMock", "next"), "next",
+                        TypeParams::of::<(T,)>(), NO_ARGS)
+    }
+}
+impl<T: 'static> Iterator for IteratorMock<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        call(&self.scenario, self.mock_id, method_type_id("IteratorMock", "next"), "next",
+             TypeParams::of::<(T,)>(), NO_ARGS, (), format_debug::<()>)
+    }
+}
+impl<T: 'static> Mocked for &'static Iterator<Item=T> {
+    type MockImpl = IteratorMock<T>;
+}
+
+mock_struct!(
+    /// Mock for `Fn() -> R` callback.
+    struct FnMock0<R>, "Fn", format!("Fn() -> {}", type_name::<R>()));
+
+impl<R: 'static> FnMock0<R> {
+    pub fn call_call(&self) -> CallMatch0<R> {
+        CallMatch0::new(self.mock_id, method_type_id("FnMock0", "call"), "call",
+                        TypeParams::of::<(R,)>(), NO_ARGS)
+    }
+
+    /// Returns closure calling this mock.
+    pub fn as_fn(&self) -> Box<Fn() -> R> {
+        let scenario = self.scenario.clone();
+        let mock_id = self.mock_id;
+        Box::new(move || {
+            call(&scenario, mock_id, method_type_id("FnMock0", "call"), "call",
+                 TypeParams::of::<(R,)>(), NO_ARGS, (), format_hidden_0)
+        })
+    }
+}
+
+mock_struct!(
+    /// Mock for `Fn(A0) -> R` callback.
+    struct FnMock1<A0, R>, "Fn", format!("Fn({}) -> {}", type_name::<A0>(), type_name::<R>()));
+
+impl<A0: 'static, R: 'static> FnMock1<A0, R> {
+    pub fn call_call<Arg0Match: MatchArg<A0> + 'static>(&self, arg0: Arg0Match) -> CallMatch1<A0, R> {
+        CallMatch1::new(self.mock_id, method_type_id("FnMock1", "call"), "call",
+                        TypeParams::of::<(A0, R)>(), CALLBACK1_ARGS, Box::new(arg0))
+    }
+
+    /// Returns closure calling this mock.
+    pub fn as_fn(&self) -> Box<Fn(A0) -> R> {
+        let scenario = self.scenario.clone();
+        let mock_id = self.mock_id;
+        Box::new(move |arg0| {
+            call(&scenario, mock_id, method_type_id("FnMock1", "call"), "call",
+                 TypeParams::of::<(A0, R)>(), CALLBACK1_ARGS, (arg0,), format_hidden_1)
+        })
+    }
+}
+
+mock_struct!(
+    /// Mock for `Fn(A0, A1) -> R` callback.
+    struct FnMock2<A0, A1, R>, "Fn",
+    format!("Fn({}, {}) -> {}", type_name::<A0>(), type_name::<A1>(), type_name::<R>()));
+
+impl<A0: 'static, A1: 'static, R: 'static> FnMock2<A0, A1, R> {
+    pub fn call_call<Arg0Match: MatchArg<A0> + 'static, Arg1Match: MatchArg<A1> + 'static>(
+            &self, arg0: Arg0Match, arg1: Arg1Match) -> CallMatch2<A0, A1, R> {
+        CallMatch2::new(self.mock_id, method_type_id("FnMock2", "call"), "call",
+                        TypeParams::of::<(A0, A1, R)>(), CALLBACK2_ARGS,
+                        Box::new(arg0), Box::new(arg1))
+    }
+
+    /// Returns closure calling this mock.
+    pub fn as_fn(&self) -> Box<Fn(A0, A1) -> R> {
+        let scenario = self.scenario.clone();
+        let mock_id = self.mock_id;
+        Box::new(move |arg0, arg1| {
+            call(&scenario, mock_id, method_type_id("FnMock2", "call"), "call",
+                 TypeParams::of::<(A0, A1, R)>(), CALLBACK2_ARGS, (arg0, arg1), format_hidden_2)
+        })
+    }
+}
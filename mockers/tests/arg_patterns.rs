@@ -0,0 +1,49 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test mocking of methods with patterns in parameter list.
+
+extern crate mockers;
+
+use mockers::Scenario;
+use mockers::matchers::ANY;
+
+#[derive(Mock)]
+#[allow(patterns_in_fns_without_body)]
+pub trait Canvas {
+    fn clear(&self, _: u32);
+    fn scale(&self, mut factor: u32) -> u32;
+    fn rect(&self, _: (i32, i32), _size: (u32, u32));
+}
+
+#[test]
+fn test_wildcard_arg() {
+    let mut scenario = Scenario::new();
+    let canvas = scenario.create_mock_for::<Canvas>();
+    scenario.expect(canvas.clear_call(0).and_return(()));
+    canvas.clear(0);
+}
+
+#[test]
+fn test_mut_binding_arg() {
+    let mut scenario = Scenario::new();
+    let canvas = scenario.create_mock_for::<Canvas>();
+    scenario.expect(canvas.scale_call(2).and_return(4));
+    assert_eq!(canvas.scale(2), 4);
+}
+
+#[test]
+fn test_wildcard_and_named_args() {
+    let mut scenario = Scenario::new();
+    let canvas = scenario.create_mock_for::<Canvas>();
+    scenario.expect(canvas.rect_call(ANY, (3, 4)).and_return(()));
+    canvas.rect((0, 0), (3, 4));
+}
+
+#[test]
+#[should_panic(expected="unexpected call to `Canvas#0.rect((3, 4), (1, 2))`")]
+fn test_wildcard_arg_format() {
+    let mut scenario = Scenario::new();
+    let canvas = scenario.create_mock_for::<Canvas>();
+    canvas.rect((3, 4), (1, 2));
+}
@@ -0,0 +1,81 @@
+#![feature(plugin, custom_derive, associated_consts)]
+#![plugin(mockers_macros)]
+
+///! Test mocking of traits with associated types and constants.
+
+extern crate mockers;
+
+use mockers::Scenario;
+
+#[derive(Mock)]
+#[mock(types(Item = "u32"), consts(NAME = "\"mocked\""))]
+pub trait Source {
+    type Item;
+    const NAME: &'static str;
+
+    fn next(&mut self) -> Option<Self::Item>;
+    fn put(&mut self, item: Self::Item);
+}
+
+#[derive(Mock)]
+#[mock(types(Item = "String"))]
+pub trait Stream {
+    type Item;
+
+    fn poll(&mut self) -> Option<<Self as Stream>::Item>;
+}
+
+mod codec {
+    pub trait Codec {
+        type Frame;
+        fn decode(&self, data: u8) -> Self::Frame;
+    }
+}
+
+mock!{
+    CodecMock,
+    codec,
+    trait Codec {
+        type Frame = String;
+        fn decode(&self, data: u8) -> Self::Frame;
+    }
+}
+
+#[test]
+fn test_assoc_type_in_result() {
+    let mut scenario = Scenario::new();
+    let mut mock = scenario.create_mock::<SourceMock>();
+    scenario.expect(mock.next_call().and_return(Some(3)));
+    assert_eq!(mock.next(), Some(3));
+}
+
+#[test]
+fn test_assoc_type_in_arg() {
+    let mut scenario = Scenario::new();
+    let mut mock = scenario.create_mock::<SourceMock>();
+    scenario.expect(mock.put_call(5).and_return(()));
+    mock.put(5);
+}
+
+#[test]
+fn test_assoc_const() {
+    assert_eq!(<SourceMock as Source>::NAME, "mocked");
+}
+
+#[test]
+fn test_create_mock_for_trait_with_assoc_type() {
+    let mut scenario = Scenario::new();
+    let mut mock = scenario.create_mock_for::<Stream<Item=String>>();
+    scenario.expect(mock.poll_call().and_return(None));
+    assert_eq!(mock.poll(), None);
+}
+
+#[test]
+fn test_assoc_type_by_macro() {
+    use codec::Codec;
+
+    let mut scenario = Scenario::new();
+    let mock = scenario.create_mock::<CodecMock>();
+    scenario.expect(mock.decode_call(1).and_return("one".to_owned()));
+    assert_eq!(mock.decode(1), "one");
+}
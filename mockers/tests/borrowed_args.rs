@@ -0,0 +1,94 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test mocking of methods with reference arguments.
+
+#[macro_use(check)]
+extern crate mockers;
+
+use mockers::Scenario;
+use mockers::matchers::{ANY, eq, lt};
+
+#[derive(Mock)]
+// `&String` is mocked as example of reference to sized type.
+#[cfg_attr(feature = "cargo-clippy", allow(ptr_arg))]
+pub trait Parser {
+    fn parse(&self, text: &str) -> u32;
+    fn feed(&mut self, data: &[u8]);
+    fn check(&self, value: &u32, name: &String) -> bool;
+    fn fill(&self, buf: &mut Vec<u8>);
+    fn first<'a>(&self, text: &'a str, other: &'a str) -> &'a str;
+}
+
+#[test]
+fn test_str_arg() {
+    let mut scenario = Scenario::new();
+    let parser = scenario.create_mock_for::<Parser>();
+    scenario.expect(parser.parse_call("abc").and_return(3));
+    let text = String::from("abc");
+    assert_eq!(parser.parse(&text), 3);
+}
+
+#[test]
+fn test_slice_arg() {
+    let mut scenario = Scenario::new();
+    let mut parser = scenario.create_mock_for::<Parser>();
+    scenario.expect(parser.feed_call(&b"ab"[..]).and_return(()));
+    let data = b"ab".to_vec();
+    parser.feed(&data);
+}
+
+#[test]
+fn test_ref_args_matched_by_value() {
+    let mut scenario = Scenario::new();
+    let parser = scenario.create_mock_for::<Parser>();
+    scenario.expect(parser.check_call(lt(5), eq("foo".to_owned())).and_return(true));
+    assert!(parser.check(&3, &"foo".to_owned()));
+}
+
+#[test]
+fn test_mut_ref_arg() {
+    let mut scenario = Scenario::new();
+    let parser = scenario.create_mock_for::<Parser>();
+    scenario.expect(parser.fill_call(check!(|v: &Vec<u8>| v.is_empty()))
+                          .and_call(|buf: &mut Vec<u8>| buf.push(1)));
+    let mut buf = Vec::new();
+    parser.fill(&mut buf);
+    assert_eq!(buf, vec![1]);
+}
+
+#[test]
+fn test_ref_arg_passed_to_action() {
+    let mut scenario = Scenario::new();
+    let parser = scenario.create_mock_for::<Parser>();
+    scenario.expect(parser.parse_call(ANY).and_call(|text| text.len() as u32));
+    let text = String::from("abcd");
+    assert_eq!(parser.parse(&text), 4);
+}
+
+#[test]
+fn test_ref_args_predicate() {
+    let mut scenario = Scenario::new();
+    let parser = scenario.create_mock_for::<Parser>();
+    scenario.expect(parser.check_call_with(|args| *args.value < 5 && args.name == "foo").and_return(true));
+    assert!(parser.check(&3, &"foo".to_owned()));
+}
+
+#[test]
+fn test_lifetime_params() {
+    let mut scenario = Scenario::new();
+    let parser = scenario.create_mock_for::<Parser>();
+    scenario.expect(parser.first_call("a", ANY).and_return("a"));
+    let a = String::from("a");
+    let b = String::from("b");
+    assert_eq!(parser.first(&a, &b), "a");
+}
+
+#[test]
+#[should_panic(expected="unexpected call to `Parser#0.parse(\"abd\")`")]
+fn test_str_arg_mismatch() {
+    let mut scenario = Scenario::new();
+    let parser = scenario.create_mock_for::<Parser>();
+    scenario.expect(parser.parse_call("abc").and_return(3));
+    parser.parse("abd");
+}
@@ -0,0 +1,66 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test matching of all call arguments together with `*_call_with`.
+
+extern crate mockers;
+
+use mockers::Scenario;
+use mockers::matchers::{ANY, lt};
+
+#[derive(Mock)]
+pub trait Store {
+    fn set(&self, key: i16, value: i32);
+    fn rename(&self, from: &str, to: &str) -> bool;
+    fn open(path: &str) -> Self;
+}
+
+#[test]
+fn test_call_with() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    scenario.expect(store.set_call_with(|args| *args.value > *args.key as i32).and_return(()));
+    store.set(1, 2);
+}
+
+#[test]
+#[should_panic(expected="arguments don't satisfy predicate")]
+fn test_call_with_mismatch() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    scenario.expect(store.set_call_with(|args| *args.value > *args.key as i32).and_return(()));
+    store.set(3, 2);
+}
+
+#[test]
+fn test_call_with_references() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    scenario.expect(store.rename_call_with(|args| args.from != args.to).and_return(true));
+    assert!(store.rename("a", "b"));
+}
+
+#[test]
+fn test_static_call_with() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    scenario.expect(StoreMock::open_call_with(|args| args.path.starts_with('/')).and_return(store));
+    let _store = <StoreMock as Store>::open("/tmp/store");
+}
+
+#[test]
+#[should_panic(expected="Some expectations are not satisfied:\n`Store#0.set(key: _, value: _) if <predicate>`")]
+fn test_call_with_describe() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    scenario.expect(store.set_call_with(|_| true).and_return(()));
+}
+
+#[test]
+#[should_panic(expected="arg `value`: ")]
+fn test_arg_names_in_notes() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    scenario.expect(store.set_call(ANY, lt(5)).and_return(()));
+    store.set(1, 7);
+}
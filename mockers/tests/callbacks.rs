@@ -0,0 +1,102 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test mocking of `extern "C"` callbacks.
+
+extern crate mockers;
+
+use std::env;
+use std::os::raw::c_void;
+use std::process::{Command, Output};
+use std::ptr;
+use mockers::Scenario;
+use mockers::matchers::ANY;
+
+#[derive(Mock)]
+pub trait Handler {
+    extern "C" fn on_data(user_data: *mut c_void, value: i32);
+    extern "C" fn on_query(user_data: *mut c_void, key: u32) -> i32;
+}
+
+type DataCallback = extern "C" fn(*mut c_void, i32);
+
+/// Imitates C library which invokes callback for each value.
+fn feed(callback: DataCallback, user_data: *mut c_void, values: &[i32]) {
+    for &value in values {
+        callback(user_data, value);
+    }
+}
+
+#[test]
+fn test_callback() {
+    let mut scenario = Scenario::new();
+    let handler = scenario.create_mock::<HandlerMock>();
+    scenario.expect(handler.on_data_call(1).and_return(()));
+    scenario.expect(handler.on_data_call(2).and_return(()));
+
+    feed(<HandlerMock as Handler>::on_data, handler.user_data(), &[1, 2]);
+}
+
+#[test]
+fn test_callback_result() {
+    let mut scenario = Scenario::new();
+    let handler = scenario.create_mock::<HandlerMock>();
+    scenario.expect(handler.on_query_call(ANY).and_return(42));
+
+    let callback: extern "C" fn(*mut c_void, u32) -> i32 = <HandlerMock as Handler>::on_query;
+    assert_eq!(callback(handler.user_data(), 7), 42);
+}
+
+#[test]
+fn test_callbacks_of_different_mocks() {
+    let mut scenario = Scenario::new();
+    let first = scenario.create_mock::<HandlerMock>();
+    let second = scenario.create_mock::<HandlerMock>();
+    scenario.expect(second.on_data_call(5).and_return(()));
+
+    feed(<HandlerMock as Handler>::on_data, second.user_data(), &[5]);
+    feed(<HandlerMock as Handler>::on_data, first.user_data(), &[]);
+}
+
+/// Runs test in child process and returns its output, it is used for
+/// tests which abort process.
+fn run_in_child(test_name: &str) -> Output {
+    Command::new(env::current_exe().unwrap())
+        .arg(test_name).arg("--nocapture")
+        .env(CHILD_ENV, "1")
+        .output().unwrap()
+}
+
+const CHILD_ENV: &'static str = "MOCKERS_CALLBACKS_CHILD";
+
+#[test]
+fn test_callback_null_user_data() {
+    if env::var(CHILD_ENV).is_ok() {
+        let mut scenario = Scenario::new();
+        let _handler = scenario.create_mock::<HandlerMock>();
+        feed(<HandlerMock as Handler>::on_data, ptr::null_mut(), &[1]);
+        return;
+    }
+
+    let output = run_in_child("test_callback_null_user_data");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("null `user_data` is passed to `on_data` callback"), "{}", stderr);
+    assert!(stderr.contains("`on_data` callback panicked, aborting"), "{}", stderr);
+}
+
+#[test]
+fn test_callback_unexpected_call() {
+    if env::var(CHILD_ENV).is_ok() {
+        let mut scenario = Scenario::new();
+        let handler = scenario.create_mock::<HandlerMock>();
+        feed(<HandlerMock as Handler>::on_data, handler.user_data(), &[1]);
+        return;
+    }
+
+    let output = run_in_child("test_callback_unexpected_call");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("unexpected call to `Handler#0.on_data(1)`"), "{}", stderr);
+    assert!(stderr.contains("`on_data` callback panicked, aborting"), "{}", stderr);
+}
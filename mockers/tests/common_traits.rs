@@ -0,0 +1,90 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test implementation of `Debug`, `Clone` and `PartialEq` by mocks.
+
+extern crate mockers;
+
+use std::fmt::{self, Debug};
+use mockers::Scenario;
+
+#[derive(Mock)]
+pub trait Counter {
+    fn inc(&self);
+}
+
+#[derive(Mock)]
+#[mock(clone)]
+pub trait Logger {
+    fn log(&self, message: &str);
+}
+
+#[derive(Mock)]
+#[mock(no_common_traits)]
+pub trait Timer {
+    fn tick(&self);
+}
+
+/// Common traits may be implemented by user when mock doesn't implement them.
+impl Debug for TimerMock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("timer")
+    }
+}
+
+fn describe<T: Debug>(value: &T) -> String {
+    format!("{:?}", value)
+}
+
+fn log_twice<L: Logger + Clone>(logger: &L) {
+    let copy = logger.clone();
+    logger.log("first");
+    copy.log("second");
+}
+
+#[test]
+fn test_debug() {
+    let mut scenario = Scenario::new();
+    let counter = scenario.create_mock_for::<Counter>();
+    let named = scenario.create_named_mock_for::<Counter>("requests".to_owned());
+    assert_eq!(describe(&counter), "Counter#0");
+    assert_eq!(describe(&named), "requests");
+}
+
+#[test]
+// Mock is compared with itself deliberately.
+#[cfg_attr(feature = "cargo-clippy", allow(eq_op))]
+fn test_partial_eq() {
+    let mut scenario = Scenario::new();
+    let first = scenario.create_mock_for::<Counter>();
+    let second = scenario.create_mock_for::<Counter>();
+    assert!(first == first);
+    assert!(first != second);
+}
+
+#[test]
+fn test_clone() {
+    let mut scenario = Scenario::new();
+    let logger = scenario.create_mock_for::<Logger>();
+    scenario.expect(logger.log_call("first").and_return(()));
+    scenario.expect(logger.log_call("second").and_return(()));
+    log_twice(&logger);
+    assert_eq!(logger.clone(), logger);
+}
+
+#[test]
+fn test_debug_in_action() {
+    let mut scenario = Scenario::new();
+    let logger = scenario.create_mock_for::<Logger>();
+    let copy = logger.clone();
+    scenario.expect(logger.log_call("first").and_call(move |_| assert_eq!(describe(&copy), "#0")));
+    logger.log("first");
+    assert_eq!(describe(&logger), "Logger#0");
+}
+
+#[test]
+fn test_no_common_traits() {
+    let mut scenario = Scenario::new();
+    let timer = scenario.create_mock_for::<Timer>();
+    assert_eq!(describe(&timer), "timer");
+}
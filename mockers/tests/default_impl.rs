@@ -0,0 +1,37 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test using default implementations of provided trait methods.
+
+extern crate mockers;
+
+use mockers::Scenario;
+
+#[derive(Mock)]
+#[mock(default_impl = "describe")]
+pub trait Shape {
+    fn name(&self) -> String;
+    fn sides(&self) -> u32;
+
+    fn describe(&self) -> String {
+        format!("{} with {} sides", self.name(), self.sides())
+    }
+}
+
+#[test]
+fn test_default_impl_calls_mocked_methods() {
+    let mut scenario = Scenario::new();
+    let shape = scenario.create_mock_for::<Shape>();
+    scenario.expect(shape.name_call().and_return("triangle".to_owned()));
+    scenario.expect(shape.sides_call().and_return(3));
+    assert_eq!(shape.describe(), "triangle with 3 sides");
+}
+
+#[test]
+#[should_panic(expected="unexpected call to `Shape#0.sides()`")]
+fn test_default_impl_unexpected_call() {
+    let mut scenario = Scenario::new();
+    let shape = scenario.create_mock_for::<Shape>();
+    scenario.expect(shape.name_call().and_return("square".to_owned()));
+    shape.describe();
+}
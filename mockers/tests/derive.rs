@@ -17,3 +17,16 @@ fn test() {
     let mut scenario = Scenario::new();
     let _mock = scenario.create_mock_for::<A>();
 }
+
+#[mockable]
+pub trait B {
+    fn bar(&self, key: i16) -> i32;
+}
+
+#[test]
+fn test_mockable_trait() {
+    let mut scenario = Scenario::new();
+    let mock = scenario.create_mock_for::<B>();
+    scenario.expect(mock.bar_call(1).and_return(2));
+    assert_eq!(mock.bar(1), 2);
+}
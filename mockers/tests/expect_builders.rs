@@ -0,0 +1,143 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test `expect_*` expectation builders.
+
+extern crate mockers;
+
+use mockers::Scenario;
+use mockers::matchers::{ANY, lt};
+
+#[derive(Mock)]
+pub trait Store {
+    fn put(&self, table: u32, key: u32, value: String, overwrite: bool) -> bool;
+    fn flush(&self);
+    fn open() -> Self;
+    fn read(&self, key: &str, buf: &mut Vec<u8>) -> usize;
+}
+
+#[test]
+fn test_any_args() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    store.expect_put().returning(|_, _, _, _| true);
+    assert!(store.put(1, 2, "value".to_owned(), false));
+}
+
+#[test]
+fn test_with() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    store.expect_put().with(1, lt(10), ANY, true).returning(|_, key, _, _| key == 5);
+    assert!(store.put(1, 5, "value".to_owned(), true));
+}
+
+#[test]
+fn test_with_ref_args() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    store.expect_read().with("key", ANY).returning(|key, buf| {
+        buf.extend_from_slice(key.as_bytes());
+        key.len()
+    });
+    let mut buf = Vec::new();
+    assert_eq!(store.read("key", &mut buf), 3);
+    assert_eq!(buf, b"key");
+}
+
+#[test]
+#[should_panic(expected="unexpected call to `Store#0.put(1, 20, \"value\", true)`")]
+fn test_with_mismatch() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    store.expect_put().with(1, lt(10), ANY, true).returning(|_, _, _, _| true);
+    store.put(1, 20, "value".to_owned(), true);
+}
+
+#[test]
+fn test_times() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    store.expect_flush().times(2);
+    store.flush();
+    store.flush();
+}
+
+#[test]
+#[should_panic(expected="Some expectations are not satisfied:\n`Store#0.flush() must be called 2 times, called 1 times`")]
+fn test_times_unsatisfied() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    store.expect_flush().times(2);
+    store.flush();
+}
+
+#[test]
+#[should_panic(expected="Some expectations are not satisfied:\n\
+                         `Store#0.put(table: _, key: _, value: _, overwrite: _) must be called 1 times, called 0 times`\n    \
+                         result: isn't given, use `returning`")]
+fn test_no_result() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    store.expect_put();
+}
+
+#[test]
+#[should_panic(expected="result of `Store#0.put(table: _, key: _, value: _, overwrite: _)` isn't given, use `returning`")]
+fn test_no_result_call() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    let _builder = store.expect_put();
+    store.put(1, 2, "value".to_owned(), false);
+}
+
+#[test]
+fn test_builder_alive_during_call() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    let builder = store.expect_put().returning(|_, _, _, _| true);
+    assert!(store.put(1, 2, "value".to_owned(), false));
+    builder.times(1);
+}
+
+#[test]
+fn test_builder_order() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    let builder = store.expect_put().returning(|_, _, _, _| true);
+    // Expectation added later takes precedence, though builder is still alive.
+    scenario.expect(store.put_call(ANY, ANY, ANY, ANY).and_return(false));
+    assert!(!store.put(1, 2, "value".to_owned(), false));
+    builder.times(0);
+}
+
+#[test]
+fn test_builder_moved_into_action() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    let builder = store.expect_flush();
+    scenario.expect(store.put_call(ANY, ANY, ANY, ANY).and_call(move |_, _, _, _| {
+        builder.times(1);
+        true
+    }));
+    assert!(store.put(1, 2, "value".to_owned(), false));
+    store.flush();
+}
+
+#[test]
+fn test_static_method() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    store.expect_flush();
+    let mut store = Some(store);
+    StoreMock::expect_open().returning(move || store.take().unwrap());
+    <StoreMock as Store>::open().flush();
+}
+
+#[test]
+fn test_call_api_still_works() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    scenario.expect(store.flush_call().and_return(()));
+    store.flush();
+}
@@ -0,0 +1,55 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test mocking of methods with `self: Box<Self>` receiver.
+
+extern crate mockers;
+
+use mockers::Scenario;
+use mockers::matchers::ANY;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Event { Start, Stop }
+
+#[derive(Mock)]
+pub trait State {
+    fn next(self: Box<Self>, event: Event) -> Box<State>;
+    fn name(&self) -> &'static str;
+}
+
+fn run(state: Box<State>, events: Vec<Event>) -> Box<State> {
+    events.into_iter().fold(state, |state, event| state.next(event))
+}
+
+#[test]
+fn test_boxed_self() {
+    let mut scenario = Scenario::new();
+    let idle = scenario.create_mock_for::<State>();
+    let running = scenario.create_mock_for::<State>();
+    scenario.expect(running.name_call().and_return("running"));
+    scenario.expect(idle.next_call(Event::Start).and_return(Box::new(running)));
+
+    let state = run(Box::new(idle), vec![Event::Start]);
+    assert_eq!(state.name(), "running");
+}
+
+#[test]
+#[should_panic(expected="unexpected call to `State#0.next(Stop)`")]
+fn test_boxed_self_unexpected_call() {
+    let mut scenario = Scenario::new();
+    let idle = scenario.create_mock_for::<State>();
+    let running = scenario.create_mock_for::<State>();
+    scenario.expect(idle.next_call(Event::Start).and_return(Box::new(running)));
+
+    run(Box::new(idle), vec![Event::Stop]);
+}
+
+#[test]
+fn test_boxed_self_any_event() {
+    let mut scenario = Scenario::new();
+    let idle = scenario.create_mock_for::<State>();
+    let stopped = scenario.create_mock_for::<State>();
+    scenario.expect(idle.next_call(ANY).and_return(Box::new(stopped)));
+
+    run(Box::new(idle), vec![Event::Stop]);
+}
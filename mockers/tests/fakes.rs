@@ -0,0 +1,102 @@
+///! Test byte-stream fakes.
+
+extern crate mockers;
+
+use std::io::{self, Read, Write, BufRead, BufReader};
+use mockers::Scenario;
+use mockers::matchers::{eq, ANY};
+use mockers::fakes::{ScriptedReader, RecordingWriter};
+
+fn read_all(reader: &mut Read) -> (Vec<u8>, usize) {
+    let mut data = Vec::new();
+    let mut blocks = 0;
+    let mut buf = [0; 4];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return (data, blocks),
+            Ok(n) => data.extend_from_slice(&buf[..n]),
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => blocks += 1,
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
+#[test]
+fn test_scripted_reader() {
+    let mut scenario = Scenario::new();
+    let mut reader = scenario.create_mock::<ScriptedReader>();
+    reader.chunk(b"hello, ").would_block().chunk("world");
+    assert_eq!(read_all(&mut reader), (b"hello, world".to_vec(), 1));
+}
+
+#[test]
+fn test_scripted_reader_error() {
+    let mut scenario = Scenario::new();
+    let mut reader = scenario.create_mock::<ScriptedReader>();
+    reader.error(io::ErrorKind::ConnectionReset, "reset");
+    assert_eq!(reader.read(&mut [0; 4]).unwrap_err().kind(), io::ErrorKind::ConnectionReset);
+}
+
+#[test]
+#[should_panic(expected="empty chunk is added to script of ScriptedReader#0, reading it would signal end of stream")]
+fn test_scripted_reader_empty_chunk() {
+    let mut scenario = Scenario::new();
+    let reader = scenario.create_mock::<ScriptedReader>();
+    reader.chunk("line\n").chunk("");
+}
+
+#[test]
+#[should_panic(expected="`ScriptedReader#0.read() must consume whole script`\n    script: 1 steps are left, next is chunk \"rest\"")]
+fn test_scripted_reader_not_consumed() {
+    let mut scenario = Scenario::new();
+    let reader = scenario.create_mock::<ScriptedReader>();
+    reader.chunk("line\n").chunk("rest");
+    let mut line = String::new();
+    BufReader::with_capacity(5, reader.clone()).read_line(&mut line).unwrap();
+}
+
+#[test]
+fn test_recording_writer() {
+    let mut scenario = Scenario::new();
+    let writer = scenario.create_mock::<RecordingWriter>();
+    writer.expect_text(eq("HELLO\n".to_owned()));
+    writer.expect_output(ANY);
+    let mut w = writer.clone();
+    write!(w, "HEL").unwrap();
+    write!(w, "LO\n").unwrap();
+    assert_eq!(writer.output(), b"HELLO\n".to_vec());
+}
+
+#[test]
+#[should_panic(expected="`RecordingWriter#0.output(\"BYE\")`\n    output: \"HI\" is not equal to \"BYE\"")]
+fn test_recording_writer_mismatch() {
+    let mut scenario = Scenario::new();
+    let mut writer = scenario.create_mock::<RecordingWriter>();
+    writer.expect_text("BYE".to_owned());
+    writer.write_all(b"HI").unwrap();
+    scenario.checkpoint();
+}
+
+#[test]
+#[should_panic(expected="`ScriptedReader#0.read() must consume whole script`\n    script: 1 steps are left, next is chunk \"again\"")]
+fn test_scripted_reader_after_checkpoint() {
+    let mut scenario = Scenario::new();
+    let mut reader = scenario.create_mock::<ScriptedReader>();
+    reader.chunk("once");
+    assert_eq!(read_all(&mut reader), (b"once".to_vec(), 0));
+    scenario.checkpoint();
+
+    reader.chunk("again");
+}
+
+#[test]
+fn test_scripted_reader_consumed_after_checkpoint() {
+    let mut scenario = Scenario::new();
+    let mut reader = scenario.create_mock::<ScriptedReader>();
+    reader.chunk("once");
+    assert_eq!(read_all(&mut reader), (b"once".to_vec(), 0));
+    scenario.checkpoint();
+
+    reader.chunk("again");
+    assert_eq!(read_all(&mut reader), (b"again".to_vec(), 0));
+}
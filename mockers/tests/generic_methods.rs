@@ -0,0 +1,69 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test mocking of generic trait methods.
+
+extern crate mockers;
+
+use mockers::Scenario;
+use mockers::matchers::ANY;
+
+#[derive(Mock)]
+pub trait A {
+    fn get<T: Default>(&self, key: u32) -> T;
+    fn put<V>(&self, value: V);
+}
+
+#[test]
+fn test_generic_result() {
+    let mut scenario = Scenario::new();
+    let mock = scenario.create_mock::<AMock>();
+    scenario.expect(mock.get_call::<String, _>(2).and_return("value".to_owned()));
+    assert_eq!(mock.get::<String>(2), "value");
+}
+
+#[test]
+fn test_generic_arg() {
+    let mut scenario = Scenario::new();
+    let mock = scenario.create_mock::<AMock>();
+    scenario.expect(mock.put_call::<u32, _>(ANY).and_return(()));
+    mock.put(3u32);
+}
+
+#[test]
+fn test_instantiations_are_distinguished() {
+    let mut scenario = Scenario::new();
+    let mock = scenario.create_mock::<AMock>();
+    scenario.expect(mock.get_call::<u32, _>(1).and_return(5));
+    scenario.expect(mock.get_call::<String, _>(1).and_return("five".to_owned()));
+    assert_eq!(mock.get::<String>(1), "five");
+    assert_eq!(mock.get::<u32>(1), 5);
+}
+
+/// Type parameters are compared with lifetimes erased, so instantiations
+/// differing in lifetimes only are the same method.
+#[test]
+fn test_lifetimes_of_type_params_are_ignored() {
+    let mut scenario = Scenario::new();
+    let mock = scenario.create_mock::<AMock>();
+    scenario.expect(mock.put_call::<&'static str, _>("value").and_return(()));
+    let value = "value".to_owned();
+    mock.put(&value[..]);
+}
+
+#[test]
+#[should_panic(expected="unexpected call to `A#0.put(_)`")]
+fn test_generic_arg_format() {
+    let mut scenario = Scenario::new();
+    let mock = scenario.create_mock::<AMock>();
+    mock.put(3u32);
+}
+
+#[test]
+#[should_panic(expected="there are active expectations for A#0.put with other type parameters")]
+fn test_other_type_params() {
+    let mut scenario = Scenario::new();
+    let mock = scenario.create_mock::<AMock>();
+    scenario.expect(mock.put_call::<u32, _>(ANY).and_return(()));
+    mock.put("str");
+}
@@ -0,0 +1,61 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test mocking of generic traits.
+
+extern crate mockers;
+
+use mockers::Scenario;
+use mockers::matchers::ANY;
+use sink::Sink;
+
+#[derive(Mock)]
+pub trait Handler<Req> {
+    fn handle(&self, req: Req) -> bool;
+}
+
+mod sink {
+    pub trait Sink<Item: Clone> {
+        fn send(&mut self, item: Item);
+    }
+}
+
+mock!{
+    SinkMock,
+    sink,
+    trait Sink<Item: Clone> {
+        fn send(&mut self, item: Item);
+    }
+}
+
+#[test]
+fn test_generic_trait() {
+    let mut scenario = Scenario::new();
+    let mock = scenario.create_mock::<HandlerMock<u32>>();
+    scenario.expect(mock.handle_call(3).and_return(true));
+    assert!(mock.handle(3));
+}
+
+#[test]
+fn test_create_mock_for_generic_trait() {
+    let mut scenario = Scenario::new();
+    let mock = scenario.create_mock_for::<Handler<String>>();
+    scenario.expect(mock.handle_call(ANY).and_return(false));
+    assert!(!mock.handle("request".to_owned()));
+}
+
+#[test]
+fn test_generic_trait_by_macro() {
+    let mut scenario = Scenario::new();
+    let mut mock = scenario.create_mock::<SinkMock<String>>();
+    scenario.expect(mock.send_call("item".to_owned()).and_return(()));
+    mock.send("item".to_owned());
+}
+
+#[test]
+#[should_panic(expected="unexpected call to `Handler<u32>#0.handle(_)`")]
+fn test_generic_trait_mock_name() {
+    let mut scenario = Scenario::new();
+    let mock = scenario.create_mock::<HandlerMock<u32>>();
+    mock.handle(3);
+}
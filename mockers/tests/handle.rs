@@ -0,0 +1,92 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test adding expectations through mock handle after mock is moved.
+
+extern crate mockers;
+
+use mockers::Scenario;
+use mockers::matchers::ANY;
+
+#[derive(Mock)]
+pub trait AirConditioner {
+    fn make_hotter(&mut self, by: i16);
+    fn get_temperature(&self) -> i16;
+}
+
+#[derive(Mock)]
+pub trait Listener {
+    fn handle(&self, event: u32);
+}
+
+struct Controller {
+    cond: Box<AirConditioner>,
+}
+
+impl Controller {
+    fn warm_up(&mut self) {
+        let t = self.cond.get_temperature();
+        self.cond.make_hotter(20 - t);
+    }
+}
+
+#[test]
+fn test_handle() {
+    let mut scenario = Scenario::new();
+    let cond = scenario.create_mock_for::<AirConditioner>();
+    let handle = cond.handle();
+    let mut controller = Controller { cond: Box::new(cond) };
+
+    scenario.expect(handle.get_temperature_call().and_return(16));
+    scenario.expect(handle.make_hotter_call(4).and_return(()));
+    controller.warm_up();
+
+    scenario.expect(handle.get_temperature_call().and_return(18));
+    handle.expect_make_hotter().with(2);
+    controller.warm_up();
+}
+
+#[test]
+fn test_handle_call_with() {
+    let mut scenario = Scenario::new();
+    let cond = scenario.create_mock_for::<AirConditioner>();
+    let handle = cond.handle();
+    let mut controller = Controller { cond: Box::new(cond) };
+
+    scenario.expect(handle.get_temperature_call().and_return(16));
+    scenario.expect(handle.make_hotter_call_with(|args| *args.by > 0).and_return(()));
+    controller.warm_up();
+}
+
+#[test]
+#[should_panic(expected="unexpected call to `AirConditioner#0.make_hotter(5)`")]
+fn test_handle_matches_mock() {
+    let mut scenario = Scenario::new();
+    let cond = scenario.create_mock_for::<AirConditioner>();
+    let handle = cond.handle().clone();
+    let mut controller = Controller { cond: Box::new(cond) };
+
+    scenario.expect(handle.get_temperature_call().and_return(15));
+    scenario.expect(handle.make_hotter_call(4).and_return(()));
+    controller.warm_up();
+}
+
+#[test]
+fn test_handle_of_other_mock() {
+    let mut scenario = Scenario::new();
+    let first = scenario.create_mock_for::<AirConditioner>();
+    let second = scenario.create_mock_for::<AirConditioner>();
+    let handle = second.handle();
+    scenario.expect(handle.get_temperature_call().and_return(3));
+    assert_eq!(second.get_temperature(), 3);
+    drop(first);
+}
+
+#[test]
+fn test_handle_new() {
+    let mut scenario = Scenario::new();
+    let listener = scenario.create_mock::<ListenerMock>();
+    let handle = ListenerMockHandle::new(&listener);
+    scenario.expect(handle.handle_call(ANY).and_return(()));
+    listener.handle(1);
+}
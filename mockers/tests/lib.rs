@@ -75,7 +75,7 @@ fn test_arg_match_success() {
 
 
 #[test]
-#[should_panic(expected="Some expectations are not satisfied:\n`A#0.bar(_)`\n")]
+#[should_panic(expected="Some expectations are not satisfied:\n`A#0.bar(arg: _)`\n")]
 fn test_expected_call_not_performed() {
     let mut scenario = Scenario::new();
     let mock = scenario.create_mock_for::<A>();
@@ -306,8 +306,8 @@ fn test_format_args() {
 // When no matching expectation found for call, expectations
 // for other mock object of the same type must be checked.
 #[test]
-// Message without ANSI codes is "expectation `A#0.bar(12)`"
-#[should_panic(expected="expectation `\x1b[1mA#0\x1b[0m.bar(12)`")]
+// Message without ANSI codes is "expectation `A#0.bar(arg: 12)`"
+#[should_panic(expected="expectation `\x1b[1mA#0\x1b[0m.bar(arg: 12)`")]
 fn test_check_other_mock_object_expectations() {
     let mut scenario = Scenario::new();
     let mock0 = scenario.create_mock_for::<A>();
@@ -0,0 +1,51 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test that attributes of trait methods are kept by mocks.
+
+extern crate mockers;
+
+use mockers::Scenario;
+
+#[derive(Mock)]
+pub trait Cache {
+    fn get(&self, key: u32) -> Option<u32>;
+
+    /// Disabled method must not be mocked, otherwise mock doesn't compile.
+    #[cfg(any())]
+    fn evict(&self, key: u32) -> DisabledType;
+
+    #[cfg(all())]
+    fn clear(&self);
+
+    #[deprecated(note="use `get` instead")]
+    fn lookup(&self, key: u32) -> Option<u32>;
+
+    #[allow(non_snake_case)]
+    fn getSize(&self) -> usize;
+}
+
+#[test]
+fn test_cfg_enabled_method() {
+    let mut scenario = Scenario::new();
+    let cache = scenario.create_mock_for::<Cache>();
+    scenario.expect(cache.clear_call().and_return(()));
+    cache.clear();
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_deprecated_method() {
+    let mut scenario = Scenario::new();
+    let cache = scenario.create_mock_for::<Cache>();
+    scenario.expect(cache.lookup_call(2).and_return(Some(3)));
+    assert_eq!(cache.lookup(2), Some(3));
+}
+
+#[test]
+fn test_lint_attributes() {
+    let mut scenario = Scenario::new();
+    let cache = scenario.create_mock_for::<Cache>();
+    scenario.expect(cache.getSize_call().and_return(5));
+    assert_eq!(cache.getSize(), 5);
+}
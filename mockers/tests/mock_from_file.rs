@@ -0,0 +1,41 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test generation of mocks for traits defined in other source file.
+
+extern crate mockers;
+
+use mockers::Scenario;
+
+#[path = "support/thermostat.rs"]
+mod thermostat;
+
+use thermostat::{Sensor, Thermostat};
+
+mock_from_file!(SensorMock, thermostat, "support/thermostat.rs", Sensor);
+mock_from_file!(ThermostatMock, thermostat, "support/thermostat.rs", Thermostat, Sensor);
+
+fn adjust<T: Thermostat>(thermostat: &mut T) {
+    let temperature = thermostat.temperature();
+    if temperature < thermostat.target() {
+        thermostat.set_target(temperature);
+    }
+}
+
+#[test]
+fn test_mock_from_file() {
+    let mut scenario = Scenario::new();
+    let sensor = scenario.create_mock::<SensorMock>();
+    scenario.expect(sensor.temperature_call().and_return(20));
+    assert_eq!(sensor.temperature(), 20);
+}
+
+#[test]
+fn test_mock_from_file_with_supertrait() {
+    let mut scenario = Scenario::new();
+    let mut thermostat = scenario.create_mock::<ThermostatMock>();
+    scenario.expect(thermostat.temperature_call().and_return(18));
+    scenario.expect(thermostat.target_call().and_return(22));
+    scenario.expect(thermostat.set_target_call(18).and_return(()));
+    adjust(&mut thermostat);
+}
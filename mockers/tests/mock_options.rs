@@ -0,0 +1,70 @@
+#![feature(plugin, custom_derive, pub_restricted)]
+#![plugin(mockers_macros)]
+
+///! Test options controlling generated mock struct.
+
+extern crate mockers;
+
+use mockers::Scenario;
+
+#[derive(Mock)]
+#[mock(name = "FakeAc", vis = "pub(crate)", cfg = "any(test, feature = \"mocks\")")]
+pub trait AirConditioner {
+    fn get_temperature(&self) -> i16;
+    fn set_temperature(&self, value: i16);
+}
+
+#[derive(Mock)]
+#[mock(name = "FakeFan")]
+pub trait Fan {
+    fn spin(&self, speed: u32);
+}
+
+mod climate {
+    pub trait Heater {
+        fn heat(&mut self, by: i16);
+    }
+}
+
+mock!{
+    HeaterMock,
+    climate,
+    #[mock(local)]
+    trait Heater {
+        fn heat(&mut self, by: i16);
+    }
+}
+
+#[test]
+fn test_name() {
+    let mut scenario = Scenario::new();
+    let ac = scenario.create_mock::<FakeAc>();
+    scenario.expect(ac.get_temperature_call().and_return(20));
+    assert_eq!(ac.get_temperature(), 20);
+}
+
+#[test]
+fn test_derived_mocked() {
+    let mut scenario = Scenario::new();
+    let _fan: FakeFan = scenario.create_mock_for::<Fan>();
+}
+
+#[test]
+fn test_macro_mocked() {
+    use climate::Heater;
+
+    let mut scenario = Scenario::new();
+    let mut heater: HeaterMock = scenario.create_mock_for::<climate::Heater>();
+    scenario.expect(heater.heat_call(2).and_return(()));
+    heater.heat(2);
+}
+
+#[test]
+fn test_vis_of_call_with() {
+    let mut scenario = Scenario::new();
+    let ac = scenario.create_mock::<FakeAc>();
+    // Arguments struct has the same visibility as mock.
+    scenario.expect(ac.set_temperature_call_with(|args: &FakeAcSetTemperatureArgs| *args.value > 0)
+                      .and_return(()));
+    ac.set_temperature(20);
+}
@@ -0,0 +1,59 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test that mock type IDs depend on mock and method signature only.
+
+extern crate mockers;
+
+use mockers::{Scenario, CallMatch};
+
+#[derive(Mock)]
+pub trait Counter {
+    fn get(&self) -> u32;
+}
+
+#[derive(Mock)]
+pub trait Limit {
+    fn get(&self) -> u32;
+}
+
+mod other {
+    use super::Counter;
+
+    mock!{
+        CounterMock,
+        self,
+        trait Counter {
+            fn get(&self) -> u32;
+        }
+    }
+}
+
+#[test]
+fn test_same_ids_for_same_mock() {
+    let mut scenario = Scenario::new();
+    let first = scenario.create_mock::<CounterMock>();
+    let second = scenario.create_mock::<CounterMock>();
+    assert_eq!(first.get_call().get_mock_type_id(), second.get_call().get_mock_type_id());
+}
+
+#[test]
+fn test_distinct_ids_for_same_method_name() {
+    let mut scenario = Scenario::new();
+    let counter = scenario.create_mock::<CounterMock>();
+    let limit = scenario.create_mock::<LimitMock>();
+    let other_counter = scenario.create_mock::<other::CounterMock>();
+    assert!(counter.get_call().get_mock_type_id() != limit.get_call().get_mock_type_id());
+    assert!(counter.get_call().get_mock_type_id() != other_counter.get_call().get_mock_type_id());
+}
+
+#[test]
+fn test_same_method_name_in_one_scenario() {
+    let mut scenario = Scenario::new();
+    let counter = scenario.create_mock::<CounterMock>();
+    let limit = scenario.create_mock::<LimitMock>();
+    scenario.expect(limit.get_call().and_return(10));
+    scenario.expect(counter.get_call().and_return(3));
+    assert_eq!(counter.get(), 3);
+    assert_eq!(limit.get(), 10);
+}
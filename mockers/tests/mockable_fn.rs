@@ -0,0 +1,71 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test mocking of free functions.
+
+#[macro_use(mock_fn)]
+extern crate mockers;
+
+use mockers::Scenario;
+use mockers::matchers::ANY;
+
+mod helpers {
+    #[mockable]
+    pub fn read_config(path: &str) -> u32 {
+        path.len() as u32
+    }
+
+    #[mockable]
+    pub fn now() -> u64 {
+        0
+    }
+}
+
+use helpers::read_config;
+
+/// Private function gets private mock, which doesn't leak it.
+#[mockable]
+fn checksum(data: &str) -> u8 {
+    data.len() as u8
+}
+
+fn load() -> u32 {
+    helpers::read_config("/etc/app.conf") + helpers::now() as u32
+}
+
+#[test]
+fn test_free_fn() {
+    let mut scenario = Scenario::new();
+    scenario.expect(mock_fn!(read_config).call("/etc/app.conf").and_return(3));
+    scenario.expect(mock_fn!(helpers::now).call().and_return(4));
+    assert_eq!(load(), 7);
+}
+
+#[test]
+#[should_panic(expected="unexpected call to `<static mockable_fn::helpers>.read_config(\"/etc/app.conf\")`")]
+fn test_free_fn_unexpected_call() {
+    let mut scenario = Scenario::new();
+    scenario.expect(mock_fn!(read_config).call("/etc/other.conf").and_return(3));
+    load();
+}
+
+#[test]
+#[should_panic(expected="Some expectations are not satisfied:\n`<static mockable_fn::helpers>.now()`")]
+fn test_free_fn_not_called() {
+    let mut scenario = Scenario::new();
+    scenario.expect(mock_fn!(helpers::now).call().and_return(4));
+}
+
+#[test]
+fn test_free_fn_any_arg() {
+    let mut scenario = Scenario::new();
+    scenario.expect(mock_fn!(read_config).call(ANY).and_return_clone(5).times(1));
+    assert_eq!(read_config("x"), 5);
+}
+
+#[test]
+fn test_private_fn() {
+    let mut scenario = Scenario::new();
+    scenario.expect(mock_fn!(checksum).call("abc").and_return(7));
+    assert_eq!(checksum("abc"), 7);
+}
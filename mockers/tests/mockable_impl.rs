@@ -0,0 +1,111 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test mocking of struct inherent impls.
+
+extern crate mockers;
+
+use mockers::Scenario;
+use mockers::matchers::ANY;
+
+#[mockable]
+pub struct AirConditioner {
+    port: i16,
+}
+
+#[mockable]
+impl AirConditioner {
+    pub fn new(port: i16) -> Self {
+        AirConditioner { port: port }
+    }
+    pub fn make_hotter(&mut self, by: i16) {
+        unimplemented!()
+    }
+    pub fn make_cooler(&mut self, by: i16) {
+        unimplemented!()
+    }
+    pub fn get_temperature(&self) -> i16 {
+        unimplemented!()
+    }
+}
+
+#[mockable]
+impl AirConditioner {
+    pub fn port(&self) -> i16 {
+        self.port
+    }
+
+    /// Disabled method must not be mocked, otherwise mock doesn't compile.
+    #[cfg(any())]
+    pub fn calibrate(&mut self) -> DisabledType {
+        unimplemented!()
+    }
+
+    #[cfg(all())]
+    pub fn reset(&mut self) {
+        unimplemented!()
+    }
+}
+
+pub fn set_temperature_20(cond: &mut AirConditioner) {
+    let t = cond.get_temperature();
+    if t < 20 {
+        cond.make_hotter(20 - t);
+    } else {
+        cond.make_cooler(t - 20);
+    }
+}
+
+#[test]
+fn test_inherent_methods() {
+    let mut scenario = Scenario::new();
+    let mut cond = scenario.create_mock::<AirConditioner>();
+    scenario.expect(cond.get_temperature_call().and_return(16));
+    scenario.expect(cond.make_hotter_call(4).and_return(()));
+    set_temperature_20(&mut cond);
+}
+
+#[test]
+fn test_inherent_constructor() {
+    let mut scenario = Scenario::new();
+    let cond = scenario.create_mock::<AirConditioner>();
+    scenario.expect(cond.get_temperature_call().and_return(20));
+    scenario.expect(AirConditioner::new_call(ANY).and_return(cond));
+    assert_eq!(AirConditioner::new(2344).get_temperature(), 20);
+}
+
+#[test]
+#[should_panic(expected="unexpected call to `AirConditioner#0.make_cooler(5)`")]
+fn test_inherent_unexpected_call() {
+    let mut scenario = Scenario::new();
+    let mut cond = scenario.create_mock::<AirConditioner>();
+    scenario.expect(cond.get_temperature_call().and_return(25));
+    set_temperature_20(&mut cond);
+}
+
+#[test]
+fn test_several_impls() {
+    let mut scenario = Scenario::new();
+    let cond = scenario.create_mock::<AirConditioner>();
+    scenario.expect(cond.port_call().and_return(2344));
+    scenario.expect(cond.get_temperature_call().and_return(20));
+    assert_eq!(cond.port(), 2344);
+    assert_eq!(cond.get_temperature(), 20);
+}
+
+#[test]
+fn test_inherent_handle() {
+    let mut scenario = Scenario::new();
+    let cond = scenario.create_mock::<AirConditioner>();
+    let handle = AirConditionerHandle::new(&cond);
+    scenario.expect(handle.port_call().and_return(2344));
+    assert_eq!(cond.port(), 2344);
+}
+
+#[test]
+fn test_cfg_enabled_inherent_method() {
+    let mut scenario = Scenario::new();
+    let mut cond = scenario.create_mock::<AirConditioner>();
+    scenario.expect(cond.reset_call().and_return(()));
+    cond.reset();
+}
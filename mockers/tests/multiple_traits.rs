@@ -0,0 +1,51 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test mocking of several traits with single mock.
+
+extern crate mockers;
+
+use mockers::Scenario;
+
+pub trait Reader {
+    fn read(&mut self) -> u8;
+}
+
+pub trait Writer {
+    fn write(&mut self, data: u8);
+}
+
+mock!{
+    ConnMock,
+    self,
+    trait Reader {
+        fn read(&mut self) -> u8;
+    }
+    trait Writer {
+        fn write(&mut self, data: u8);
+    }
+}
+
+fn echo<T: Reader + Writer>(conn: &mut T) {
+    let data = conn.read();
+    conn.write(data);
+}
+
+#[test]
+fn test_multiple_traits() {
+    let mut scenario = Scenario::new();
+    let mut conn = scenario.create_mock::<ConnMock>();
+    scenario.expect(conn.read_call().and_return(5));
+    scenario.expect(conn.write_call(5).and_return(()));
+    echo(&mut conn);
+}
+
+#[test]
+#[should_panic(expected="unexpected call to `Reader+Writer#0.write(6)`")]
+fn test_multiple_traits_name() {
+    let mut scenario = Scenario::new();
+    let mut conn = scenario.create_mock::<ConnMock>();
+    scenario.expect(conn.read_call().and_return(5));
+    conn.read();
+    conn.write(6);
+}
@@ -0,0 +1,86 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test mocking of static trait methods.
+
+extern crate mockers;
+
+use mockers::Scenario;
+use mockers::matchers::ANY;
+
+#[derive(Mock)]
+pub trait Connection {
+    fn open(address: String) -> Self;
+    fn protocol() -> &'static str;
+    fn send(&self, data: u32);
+}
+
+fn connect<C: Connection>(address: &str) -> C {
+    C::open(address.to_owned())
+}
+
+#[test]
+fn test_static_method() {
+    let mut scenario = Scenario::new();
+    scenario.expect(ConnectionMock::protocol_call().and_return("tcp"));
+    assert_eq!(<ConnectionMock as Connection>::protocol(), "tcp");
+}
+
+#[test]
+fn test_constructor() {
+    let mut scenario = Scenario::new();
+    let conn = scenario.create_mock::<ConnectionMock>();
+    scenario.expect(conn.send_call(3).and_return(()));
+    scenario.expect(ConnectionMock::open_call(ANY).and_return(conn));
+
+    let conn: ConnectionMock = connect("localhost");
+    conn.send(3);
+}
+
+#[test]
+#[should_panic(expected="unexpected call to `<static Connection>.open(\"localhost\")`")]
+fn test_unexpected_static_call() {
+    let _scenario = Scenario::new();
+    let _conn: ConnectionMock = connect("localhost");
+}
+
+#[test]
+#[should_panic(expected="Some expectations are not satisfied:\n`<static Connection>.protocol()`")]
+fn test_unsatisfied_static_call() {
+    let mut scenario = Scenario::new();
+    scenario.expect(ConnectionMock::protocol_call().and_return("tcp"));
+}
+
+#[test]
+#[should_panic(expected="there is no scenario in current thread")]
+fn test_no_scenario() {
+    <ConnectionMock as Connection>::protocol();
+}
+
+#[test]
+fn test_latest_scenario_is_used() {
+    let mut outer = Scenario::new();
+    {
+        let mut inner = Scenario::new();
+        inner.expect(ConnectionMock::protocol_call().and_return("udp"));
+        assert_eq!(<ConnectionMock as Connection>::protocol(), "udp");
+    }
+    outer.expect(ConnectionMock::protocol_call().and_return("tcp"));
+    assert_eq!(<ConnectionMock as Connection>::protocol(), "tcp");
+}
+
+#[test]
+fn test_static_mock_name_is_reserved() {
+    let mut scenario = Scenario::new();
+    scenario.expect(ConnectionMock::protocol_call().and_return("tcp"));
+    assert_eq!(<ConnectionMock as Connection>::protocol(), "tcp");
+    let conn = scenario.create_named_mock::<ConnectionMock>("Connection".to_owned());
+    assert_eq!(format!("{:?}", conn), "Connection");
+}
+
+#[test]
+#[should_panic(expected="Mock name <static Connection> is reserved for static methods")]
+fn test_static_mock_name_is_not_allowed() {
+    let mut scenario = Scenario::new();
+    scenario.create_named_mock::<ConnectionMock>("<static Connection>".to_owned());
+}
@@ -0,0 +1,130 @@
+#![cfg(feature="std_mocks")]
+
+///! Test ready-made mocks for `std` traits.
+
+extern crate mockers;
+
+use std::io::{self, Read, Write, Seek, SeekFrom, BufRead};
+use mockers::{Scenario, Sequence, CallMatch};
+use mockers::matchers::ANY;
+use mockers::std_mocks::{ReadMock, SeekMock, BufReadMock, IteratorMock, FnMock1, FnMock2};
+
+#[test]
+fn test_read() {
+    let mut scenario = Scenario::new();
+    let mut reader = scenario.create_mock_for::<Read>();
+    let mut seq = Sequence::new();
+    seq.expect(reader.read_call(ANY).and_call(|buf: &mut [u8]| {
+        buf[..3].copy_from_slice(b"abc");
+        Ok(3)
+    }));
+    seq.expect(reader.read_call(ANY).and_return(Ok(0)));
+    scenario.expect(seq);
+
+    let mut data = String::new();
+    reader.read_to_string(&mut data).unwrap();
+    assert_eq!(data, "abc");
+}
+
+#[test]
+fn test_write() {
+    let mut scenario = Scenario::new();
+    let mut writer = scenario.create_mock_for::<Write>();
+    scenario.expect(writer.write_call(&b"hello"[..]).and_return(Ok(5)));
+    scenario.expect(writer.flush_call().and_return(Ok(())));
+
+    writer.write_all(b"hello").unwrap();
+    writer.flush().unwrap();
+}
+
+#[test]
+#[should_panic(expected="unexpected call to `Write#0.write([1, 2])`")]
+fn test_write_unexpected() {
+    let mut scenario = Scenario::new();
+    let mut writer = scenario.create_mock_for::<Write>();
+    scenario.expect(writer.write_call(&b"\x01"[..]).and_return(Ok(1)));
+
+    let _ = writer.write(&[1, 2]);
+}
+
+#[test]
+fn test_seek() {
+    let mut scenario = Scenario::new();
+    let mut seeker = scenario.create_mock::<SeekMock>();
+    scenario.expect(seeker.seek_call(SeekFrom::End(0)).and_return(Ok(10)));
+    assert_eq!(seeker.seek(SeekFrom::End(0)).unwrap(), 10);
+}
+
+#[test]
+fn test_buf_read() {
+    let mut scenario = Scenario::new();
+    let mut reader = scenario.create_mock::<BufReadMock>();
+    scenario.expect(reader.fill_buf_call().and_return(Ok(&b"line\nrest"[..])));
+    scenario.expect(reader.consume_call(5).and_return(()));
+
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    assert_eq!(line, "line\n");
+}
+
+#[test]
+fn test_iterator() {
+    let mut scenario = Scenario::new();
+    let iter = scenario.create_mock_for::<Iterator<Item=u32>>();
+    let mut seq = Sequence::new();
+    seq.expect(iter.next_call().and_return(Some(1)));
+    seq.expect(iter.next_call().and_return(None));
+    scenario.expect(seq);
+    assert_eq!(iter.collect::<Vec<_>>(), vec![1]);
+}
+
+#[test]
+fn test_iterator_type_name() {
+    let mut scenario = Scenario::new();
+    let iter = scenario.create_mock::<IteratorMock<u32>>();
+    assert!(format!("{:?}", iter).starts_with("Iterator<"));
+}
+
+#[test]
+fn test_callbacks() {
+    let mut scenario = Scenario::new();
+    let on_data = scenario.create_mock::<FnMock1<u32, bool>>();
+    let on_pair = scenario.create_mock::<FnMock2<u32, u32, ()>>();
+    scenario.expect(on_data.call_call(3).and_return(true));
+    scenario.expect(on_pair.call_call(1, ANY).and_return(()));
+
+    let callback = on_data.as_fn();
+    assert!(callback(3));
+    on_pair.as_fn()(1, 2);
+}
+
+#[test]
+fn test_io_error() {
+    let mut scenario = Scenario::new();
+    let mut reader = scenario.create_mock_for::<Read>();
+    scenario.expect(reader.read_call(ANY).and_call(|_: &mut [u8]| {
+        Err(io::Error::new(io::ErrorKind::Other, "boom"))
+    }));
+    assert_eq!(reader.read(&mut [0; 4]).unwrap_err().kind(), io::ErrorKind::Other);
+}
+
+#[test]
+fn test_debug_in_action() {
+    let mut scenario = Scenario::new();
+    let mut seeker = scenario.create_mock::<SeekMock>();
+    let other = scenario.create_mock::<SeekMock>();
+    assert_eq!(format!("{:?}", other), "Seek#1");
+    scenario.expect(seeker.seek_call(ANY).and_call(move |_| {
+        assert_eq!(format!("{:?}", other), "#1");
+        Ok(0)
+    }));
+    seeker.seek(SeekFrom::End(0)).unwrap();
+}
+
+#[test]
+fn test_distinct_type_ids() {
+    let mut scenario = Scenario::new();
+    let reader = scenario.create_mock::<ReadMock>();
+    let buf_reader = scenario.create_mock::<BufReadMock>();
+    assert!(reader.read_call(ANY).get_mock_type_id() != buf_reader.read_call(ANY).get_mock_type_id());
+}
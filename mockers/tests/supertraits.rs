@@ -0,0 +1,75 @@
+#![feature(plugin, custom_derive)]
+#![plugin(mockers_macros)]
+
+///! Test mocking of traits with supertraits.
+
+extern crate mockers;
+
+use std::fmt::Debug;
+use mockers::Scenario;
+
+#[derive(Mock)]
+pub trait Store: Debug + Clone + PartialEq {
+    fn get(&self, key: u32) -> u32;
+}
+
+pub trait Flush {
+    fn flush(&mut self);
+}
+
+pub trait Writer: Flush {
+    fn write(&mut self, data: u32);
+}
+
+mock!{
+    WriterMock,
+    self,
+    trait Writer: Flush {
+        fn write(&mut self, data: u32);
+    },
+    self,
+    trait Flush {
+        fn flush(&mut self);
+    }
+}
+
+fn write_all<W: Writer>(writer: &mut W, data: &[u32]) {
+    for &d in data {
+        writer.write(d);
+    }
+    writer.flush();
+}
+
+#[test]
+fn test_marker_supertraits() {
+    let mut scenario = Scenario::new();
+    let store = scenario.create_mock::<StoreMock>();
+    let copy = store.clone();
+    assert_eq!(format!("{:?}", store), "Store#0");
+    assert_eq!(store, copy);
+    assert!(store != scenario.create_mock::<StoreMock>());
+
+    // Clone shares expectations with original mock.
+    scenario.expect(store.get_call(1).and_return(2));
+    assert_eq!(copy.get(1), 2);
+}
+
+#[test]
+fn test_mocked_supertrait() {
+    let mut scenario = Scenario::new();
+    let mut writer = scenario.create_mock::<WriterMock>();
+    scenario.expect(writer.write_call(1).and_return(()));
+    scenario.expect(writer.write_call(2).and_return(()));
+    scenario.expect(writer.flush_call().and_return(()));
+    write_all(&mut writer, &[1, 2]);
+}
+
+#[test]
+#[should_panic(expected="Some expectations are not satisfied:\n`Writer#0.flush()`")]
+fn test_supertrait_expectations_are_shared() {
+    let mut scenario = Scenario::new();
+    let mut writer = scenario.create_mock::<WriterMock>();
+    scenario.expect(writer.write_call(1).and_return(()));
+    scenario.expect(writer.flush_call().and_return(()));
+    writer.write(1);
+}
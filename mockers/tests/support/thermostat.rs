@@ -0,0 +1,11 @@
+//! Traits used by `mock_from_file` test, their definitions are
+//! read by `mock_from_file!` from this file.
+
+pub trait Sensor {
+    fn temperature(&self) -> i32;
+}
+
+pub trait Thermostat: Sensor {
+    fn set_target(&mut self, target: i32);
+    fn target(&self) -> i32;
+}
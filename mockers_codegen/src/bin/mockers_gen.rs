@@ -0,0 +1,22 @@
+//! Generates source code of mocks for traits defined in given file,
+//! so that it may be committed and used without compiler plugins
+//! or build scripts. See `mockers_codegen::cli` for usage.
+
+extern crate mockers_codegen;
+
+use std::process;
+
+#[cfg(feature = "with-syntex")]
+fn main() {
+    let status = mockers_codegen::cli::main(std::env::args().skip(1), &mut std::io::stderr());
+    process::exit(status);
+}
+
+// Cargo before 0.17 ignores `required-features` and builds binary
+// in default configuration too.
+#[cfg(not(feature = "with-syntex"))]
+fn main() {
+    use std::io::Write;
+    let _ = writeln!(std::io::stderr(), "mockers-gen must be built with `with-syntex` feature");
+    process::exit(2);
+}
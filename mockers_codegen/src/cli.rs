@@ -0,0 +1,119 @@
+//! Implementation of `mockers-gen` tool, it is here and not in binary
+//! itself so that it may be tested.
+//!
+//! ```text
+//! mockers-gen [--check] [--module PATH] --output FILE SOURCE TRAIT...
+//! ```
+//!
+//! Each `TRAIT` gets mock named `{TRAIT}Mock`. Supertraits mocked together
+//! with trait are listed after it with `+`, like `Thermostat+Sensor`.
+//! `--module` gives path to module containing traits as seen from code
+//! where output is included, it is `self` by default.
+//!
+//! With `--check` output file isn't written, instead command fails if
+//! it differs from freshly generated code.
+
+use std::fs::File;
+use std::io::{Read, Write};
+
+pub const USAGE: &'static str =
+    "Usage: mockers-gen [--check] [--module PATH] --output FILE SOURCE TRAIT...";
+
+/// Exit status for invalid command line.
+pub const EXIT_USAGE: i32 = 2;
+/// Exit status for failed generation or stale output in `--check` mode.
+pub const EXIT_FAILURE: i32 = 1;
+
+#[derive(Debug, PartialEq)]
+pub struct Options {
+    pub check: bool,
+    pub module: String,
+    pub output: String,
+    pub source: String,
+    pub traits: Vec<String>,
+}
+
+/// Parses command line arguments, program name must be skipped.
+pub fn parse_args<I: Iterator<Item=String>>(mut args: I) -> Result<Options, String> {
+    let mut check = false;
+    let mut module = "self".to_owned();
+    let mut output = None;
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        match &*arg {
+            "--check" => check = true,
+            "--module" => module = try!(args.next().ok_or("module path expected after `--module`")),
+            "--output" | "-o" => output = Some(try!(args.next().ok_or("file expected after `--output`"))),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => positional.push(arg),
+        }
+    }
+    let output = try!(output.ok_or("output file must be given with `--output`"));
+    if positional.len() < 2 {
+        return Err("source file and at least one trait name are expected".to_owned());
+    }
+    let source = positional.remove(0);
+    Ok(Options {
+        check: check,
+        module: module,
+        output: output,
+        source: source,
+        traits: positional,
+    })
+}
+
+/// Generates code of all requested mocks.
+pub fn generate(options: &Options) -> Result<String, String> {
+    let mut code = format!("// Generated by mockers-gen from `{}`, do not edit.\n\n", options.source);
+    for traits in &options.traits {
+        let trait_names: Vec<&str> = traits.split('+').collect();
+        let mock_name = format!("{}Mock", trait_names[0]);
+        let mock = try!(::mock_from_file_str(&mock_name, &options.module,
+                                             &options.source, &trait_names)
+            .map_err(|err| format!("can't generate mock for `{}`: {:?}", traits, err)));
+        code.push_str(&mock);
+        code.push('\n');
+    }
+    Ok(code)
+}
+
+/// Checks that `existing` output is the same as freshly generated `code`.
+pub fn check_output(output: &str, existing: &str, code: &str) -> Result<(), String> {
+    if existing != code {
+        return Err(format!("`{}` is stale, rerun mockers-gen without `--check`", output));
+    }
+    Ok(())
+}
+
+/// Generates mocks and writes them to output file or, with `--check`,
+/// compares them with it.
+pub fn run(options: &Options) -> Result<(), String> {
+    let code = try!(generate(options));
+    if options.check {
+        let mut existing = String::new();
+        try!(File::open(&options.output).and_then(|mut file| file.read_to_string(&mut existing))
+            .map_err(|err| format!("can't read `{}`: {}", options.output, err)));
+        try!(check_output(&options.output, &existing, &code));
+    } else {
+        try!(File::create(&options.output).and_then(|mut file| file.write_all(code.as_bytes()))
+            .map_err(|err| format!("can't write `{}`: {}", options.output, err)));
+    }
+    Ok(())
+}
+
+/// Runs tool with given arguments, program name must be skipped.
+/// Errors are written to `stderr`, exit status is returned.
+pub fn main<I: Iterator<Item=String>, W: Write>(args: I, stderr: &mut W) -> i32 {
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(err) => {
+            writeln!(stderr, "{}\n{}", err, USAGE).unwrap();
+            return EXIT_USAGE;
+        }
+    };
+    if let Err(err) = run(&options) {
+        writeln!(stderr, "{}", err).unwrap();
+        return EXIT_FAILURE;
+    }
+    0
+}
@@ -2,12 +2,14 @@ extern crate itertools;
 
 use syntax::abi::Abi;
 use syntax::ast::{Item, ItemKind, TraitItemKind, Unsafety, Constness, SelfKind,
-                  PatKind, SpannedIdent, Expr, FunctionRetTy, TyKind, Generics, WhereClause,
+                  PatKind, Expr, FunctionRetTy, TyKind, Generics,
                   ImplPolarity, MethodSig, FnDecl, Mutability, ImplItem, Ident, TraitItem,
                   Visibility, ImplItemKind, Arg, Ty, TyParam, Path, PathSegment,
-                  TyParamBound, Defaultness, MetaItem,
-                  DUMMY_NODE_ID};
-#[cfg(feature="with-syntex")] use syntax::ast::PathParameters;
+                  TyParamBound, TraitBoundModifier, Defaultness, MetaItem, Attribute, NestedMetaItem,
+                  TypeBinding, Mac, Lifetime, StructField, VariantData, DUMMY_NODE_ID,
+                  LitKind, StrStyle, NestedMetaItemKind};
+use syntax::attr;
+use syntax::ast::PathParameters;
 use syntax::codemap::{Span, Spanned, respan, DUMMY_SP};
 use syntax::ext::base::{DummyResult, ExtCtxt, MacResult, MacEager, Annotatable};
 #[cfg(not(feature="with-syntex"))] use syntax::ext::quote::rt::ToTokens;
@@ -21,37 +23,41 @@ use syntax::util::small_vector::SmallVector;
 use syntax::print::pprust;
 use syntax::tokenstream::TokenTree;
 
+use syntax::fold::{self, Folder};
+use syntax::parse;
+
 use syntax::ext::build::AstBuilder;
 use itertools::Itertools;
 
-/// Each mock struct generated with `#[derive(Mock)]` or `mock!` gets
-/// unique type ID. It is added to both call matchers produced by
-/// `*_call` methods and to `Call` structure created by mocked method.
-/// It is same to use call matcher for inspecting call object only when
-/// both mock type ID and method name match.
-static mut NEXT_MOCK_TYPE_ID: usize = 0;
+use std::collections::HashMap;
+
+/// Each mocked method gets unique type ID. It is added to both call
+/// matchers produced by `*_call` methods and to `Call` structure created
+/// by mocked method. It is same to use call matcher for inspecting call
+/// object only when both mock type ID and method name match.
+///
+/// ID is computed by `mockers::mock_type_id` from key made of crate name,
+/// module path, mock name, trait path and method signature, so it doesn't
+/// depend on expansion order and is the same for separate `expand` runs.
+/// Ready-made mocks from `mockers::std_mocks` use the same function.
+fn mock_type_id(cx: &ExtCtxt, sp: Span, mock_key: &str, method_ident: Ident,
+                args: &[Arg], return_type: &Ty) -> P<Expr> {
+    let signature = format!("{}::{}::{}::{}({}) -> {}",
+                            cx.ecfg.crate_name, cx.current_expansion.module.mod_path.iter().join("::"),
+                            mock_key, method_ident,
+                            args.iter().map(|a| pprust::ty_to_string(&a.ty)).join(", "),
+                            pprust::ty_to_string(return_type));
+    cx.expr_call_global(sp, vec![cx.ident_of("mockers"), cx.ident_of("mock_type_id")],
+                        vec![cx.expr_str(sp, Symbol::intern(&signature))])
+}
 
 #[allow(unused)]
 pub fn derive_mock(cx: &mut ExtCtxt, span: Span, meta_item: &MetaItem, ann_item: &Annotatable,
                    push: &mut FnMut(Annotatable)) {
-    let (ident, subitems) = match *ann_item {
+    let item = match *ann_item {
         Annotatable::Item(ref item) =>
             match item.node {
-                ItemKind::Trait(unsafety, ref generics, ref param_bounds, ref subitems) => {
-                    if unsafety != Unsafety::Normal {
-                        cx.span_err(span, "Unsafe traits are not supported yet");
-                        return;
-                    }
-
-                    if generics.is_parameterized() {
-                        cx.span_err(span, "Parametrized traits are not supported yet");
-                        return;
-                    }
-
-                    assert!(param_bounds.is_empty());
-
-                    (item.ident, subitems)
-                },
+                ItemKind::Trait(..) => item,
                 _ => {
                     cx.span_err(span, "Deriving Mock is possible for traits only");
                     return;
@@ -62,13 +68,23 @@ pub fn derive_mock(cx: &mut ExtCtxt, span: Span, meta_item: &MetaItem, ann_item:
             return;
         }
     };
-    let mock_ident = cx.ident_of(&format!("{}Mock", ident.name.as_str()));
-    let trait_path = cx.path_ident(span, ident);
+    let trait_path = cx.path_ident(span, item.ident);
 
-    let generated_items = generate_mock_for_trait(cx, span, mock_ident, &trait_path, subitems, true);
+    let mocked = match mocked_trait(cx, span, trait_path, item) {
+        Some(mocked) => mocked,
+        None => return,
+    };
+    let mock_ident = mocked.options.name.unwrap_or_else(|| {
+        cx.ident_of(&format!("{}Mock", item.ident.name.as_str()))
+    });
+    let cfg_attr = match mocked.options.cfg {
+        Some(ref cfg) => cx.attribute(span, cfg.clone()),
+        None => quote_attr!(cx, #[cfg(test)]),
+    };
+    let generated_items = generate_mock_for_traits(cx, span, mock_ident, &[mocked], true);
     for item in generated_items {
         let item = item.map(|mut it| {
-            it.attrs.push(quote_attr!(cx, #[cfg(test)]));
+            it.attrs.push(cfg_attr.clone());
             it
         });
         debug_item(&item);
@@ -76,6 +92,227 @@ pub fn derive_mock(cx: &mut ExtCtxt, span: Span, meta_item: &MetaItem, ann_item:
     }
 }
 
+/// Make struct or its inherent impl or free function mockable.
+///
+/// Original struct and its impl are left for non-test builds, and in
+/// test builds they are replaced with mock struct with the same name.
+/// Mock has the same inherent methods and `*_call` methods for them,
+/// so code using struct may be tested without extracting trait.
+///
+/// Both struct and its impls must be marked with `#[mockable]`, struct
+/// may have several impls.
+///
+/// Free function is replaced in test builds with one verifying call
+/// against current scenario of the thread.
+///
+/// Trait gets mock just like with `#[derive(Mock)]`.
+#[allow(unused)]
+pub fn mockable(cx: &mut ExtCtxt, span: Span, meta_item: &MetaItem,
+                ann_item: Annotatable) -> Vec<Annotatable> {
+    let item = match ann_item {
+        Annotatable::Item(item) => item,
+        other => {
+            cx.span_err(span, "`#[mockable]` may be used for traits, structs, inherent impls and functions only");
+            return vec![other];
+        }
+    };
+
+    let mut generated_items = Vec::new();
+    match item.node {
+        // Trait is left as is, mock is generated like for `#[derive(Mock)]`.
+        ItemKind::Trait(..) => {
+            let mut result = vec![Annotatable::Item(item.clone())];
+            derive_mock(cx, span, meta_item, &Annotatable::Item(item.clone()), &mut |it| result.push(it));
+            return result;
+        },
+        ItemKind::Struct(_, ref generics) => {
+            if !generics.lifetimes.is_empty() || !generics.ty_params.is_empty() {
+                cx.span_err(span, "`#[mockable]` is not supported for generic structs");
+                return vec![Annotatable::Item(item.clone())];
+            }
+            generated_items = generate_mock_for_struct(cx, span, &item);
+        },
+        ItemKind::Impl(unsafety, _, ref generics, None, ref self_ty, ref impl_items) => {
+            if unsafety != Unsafety::Normal || !generics.lifetimes.is_empty() ||
+               !generics.ty_params.is_empty() {
+                cx.span_err(span, "`#[mockable]` is not supported for generic or unsafe impls");
+                return vec![Annotatable::Item(item.clone())];
+            }
+            let mock_ident = match self_ty.node {
+                TyKind::Path(None, ref path) if path.segments.len() == 1 => path.segments[0].identifier,
+                _ => {
+                    cx.span_err(self_ty.span, "`#[mockable]` impl must be for struct defined in the same module");
+                    return vec![Annotatable::Item(item.clone())];
+                }
+            };
+            generated_items = generate_mock_for_inherent_impl(cx, span, mock_ident, generics, impl_items);
+        },
+        ItemKind::Fn(ref decl, unsafety, constness, abi, ref generics, _) => {
+            if unsafety != Unsafety::Normal || constness.node != Constness::NotConst || abi != Abi::Rust {
+                cx.span_err(span, "`#[mockable]` is not supported for unsafe, const or non-Rust ABI functions");
+                return vec![Annotatable::Item(item.clone())];
+            }
+            generated_items = generate_mock_for_fn(cx, span, &item, decl, generics);
+        },
+        _ => {
+            cx.span_err(span, "`#[mockable]` may be used for traits, structs, inherent impls and functions only");
+            return vec![Annotatable::Item(item.clone())];
+        }
+    }
+
+    let item = item.map(|mut it| {
+        it.attrs.push(quote_attr!(cx, #[cfg(not(test))]));
+        it
+    });
+    let mut result = vec![Annotatable::Item(item)];
+    for item in generated_items {
+        let item = item.map(|mut it| {
+            it.attrs.push(quote_attr!(cx, #[cfg(test)]));
+            it
+        });
+        debug_item(&item);
+        result.push(Annotatable::Item(item));
+    }
+    result
+}
+
+/// Generate mock struct replacing given one, with the same visibility.
+///
+/// Mock gets methods from `#[mockable]` impls of struct, there may be
+/// several of them. Mocked methods may be named `handle`, so handle is
+/// created with `{Mock}Handle::new` only.
+///
+/// Only `no_common_traits` option is applicable to struct, it is used
+/// when common traits are implemented for mock by user.
+fn generate_mock_for_struct(cx: &mut ExtCtxt, sp: Span, item: &Item) -> Vec<P<Item>> {
+    let mock_ident = item.ident;
+    let mock_ty = cx.ty_ident(sp, mock_ident);
+    let class_name = mock_ident.name.as_str().to_string();
+    let type_name_expr = quote_expr!(cx, $class_name.to_owned());
+    let options = parse_mock_options(cx, &item.attrs);
+    let mut items = generate_mock_struct(cx, sp, mock_ident, &Generics::default(), Some(item.vis.clone()),
+                                         class_name, type_name_expr);
+    if !options.no_common_traits {
+        for name in &["Debug", "PartialEq", "Eq"] {
+            items.extend(generate_marker_impl(cx, mock_ident, &mock_ty, name));
+        }
+    }
+    items
+}
+
+/// Generate methods of mock for given inherent impl, mock struct itself
+/// is generated from `#[mockable]` struct.
+fn generate_mock_for_inherent_impl(cx: &mut ExtCtxt, sp: Span, mock_ident: Ident,
+                                   generics: &Generics, impl_items: &[ImplItem]) -> Vec<P<Item>> {
+    let mut members = Vec::new();
+    let mut visibilities = HashMap::new();
+    for impl_item in impl_items {
+        let node = match impl_item.node {
+            ImplItemKind::Method(ref sig, _) => TraitItemKind::Method(sig.clone(), None),
+            ImplItemKind::Const(ref ty, ref expr) => TraitItemKind::Const(ty.clone(), Some(expr.clone())),
+            _ => {
+                cx.span_err(impl_item.span, "only methods and constants are supported in `#[mockable]` impl");
+                continue;
+            }
+        };
+        visibilities.insert(impl_item.ident.name, impl_item.vis.clone());
+        // Attributes are filtered by `propagated_attrs` like ones of trait items.
+        members.push(TraitItem {
+            id: DUMMY_NODE_ID,
+            ident: impl_item.ident,
+            attrs: impl_item.attrs.clone(),
+            node: node,
+            span: impl_item.span,
+        });
+    }
+
+    let mocked = MockedTrait {
+        path: cx.path_ident(sp, mock_ident),
+        generics: generics,
+        bounds: &[],
+        members: &members,
+        options: MockOptions::default(),
+        inherent_vis: Some(visibilities),
+    };
+    generate_mock_for_traits(cx, sp, mock_ident, &[mocked], false)
+}
+
+/// Generate mocked version of free function.
+///
+/// For `fn read_config(path: &Path) -> Config` following items are generated:
+/// ```
+/// fn read_config(arg0: &Path) -> Config {
+///     /* verify call against current scenario */
+/// }
+///
+/// #[allow(non_camel_case_types)]
+/// pub struct read_config {}
+/// impl read_config {
+///     pub fn mock_fn() -> Self { read_config {} }
+///     pub fn call<Arg0Match: ::mockers::MatchArg<Path>>(&self, arg0: Arg0Match)
+///         -> ::mockers::CallMatch1<Path, Config, ::mockers::ByRef> { … }
+/// }
+/// ```
+/// Struct with braces occupies type namespace only, so it doesn't conflict
+/// with function. `mock_fn!(read_config)` expands to `<read_config>::mock_fn()`.
+/// Calls are attributed to pseudo-mock named after module.
+///
+/// Struct and its methods have the same visibility as function, so
+/// `pub` is used above for `pub fn` only.
+fn generate_mock_for_fn(cx: &mut ExtCtxt, sp: Span, fn_item: &Item,
+                        decl: &FnDecl, generics: &Generics) -> Vec<P<Item>> {
+    let ident = fn_item.ident;
+    let vis = fn_item.vis.clone();
+    let args = &decl.inputs[..];
+    let return_type = match decl.output {
+        FunctionRetTy::Default(span) => cx.ty(span, TyKind::Tup(vec![])),
+        FunctionRetTy::Ty(ref ty) => ty.clone(),
+    };
+    let mock_type_id = mock_type_id(cx, sp, "fn", ident, args, &return_type);
+    let no_generics = Generics::default();
+    let mock_id_source = MockIdSource::Static(quote_expr!(cx, module_path!()));
+
+    let mocked_fn = generate_trait_impl_method(cx, sp, mock_type_id.clone(), &no_generics, ident, generics,
+                                               &mock_id_source, None, args, &return_type);
+    let call_method = generate_impl_method(cx, sp, mock_type_id, &no_generics, ident, generics,
+                                           &mock_id_source, true, args, &return_type);
+    let (mocked_fn, mut call_method) = match (mocked_fn, call_method) {
+        (Some(mocked_fn), Some(call_method)) => (mocked_fn, call_method),
+        _ => return vec![],
+    };
+    call_method.ident = cx.ident_of("call");
+    call_method.vis = vis.clone();
+
+    let fn_item = match mocked_fn.node {
+        ImplItemKind::Method(sig, body) => {
+            cx.item(sp, ident, mocked_fn.attrs,
+                    ItemKind::Fn(sig.decl, sig.unsafety, sig.constness, sig.abi, sig.generics, body))
+              .map(|mut it| { it.vis = vis.clone(); it })
+        },
+        _ => unreachable!(),
+    };
+
+    let struct_item = quote_item!(cx,
+        #[allow(non_camel_case_types)]
+        pub struct $ident {}
+    ).unwrap().map(|mut it| { it.vis = vis.clone(); it });
+    let impl_item = quote_item!(cx,
+        impl $ident {
+            pub fn mock_fn() -> Self { $ident {} }
+        }
+    ).unwrap().map(|mut it| {
+        if let ItemKind::Impl(_, _, _, _, _, ref mut items) = it.node {
+            for item in items.iter_mut() {
+                item.vis = vis.clone();
+            }
+            items.push(call_method);
+        }
+        it
+    });
+
+    vec![fn_item, struct_item, impl_item]
+}
+
 fn parse_macro_args<'a>(parser: &mut Parser<'a>) -> PResult<'a, Ident> {
     let mock_ident = try!(parser.parse_ident());
     try!(parser.expect(&Token::Comma));
@@ -103,211 +340,1234 @@ pub fn generate_mock<'cx>(cx: &'cx mut ExtCtxt, sp: Span, args: &[TokenTree]) ->
     }
 }
 
+/// Parse mocked trait definitions and generate mock for them.
+///
+/// Several traits may be mocked by single mock struct, including
+/// supertraits of mocked trait. Each group of traits is preceded with
+/// module path:
+/// ```
+/// mock!{
+///     FooMock,
+///     self,
+///     trait Foo: Bar { … }
+///     trait Baz { … },
+///     ::path::to::bar::module,
+///     trait Bar { … }
+/// }
+/// ```
 pub fn generate_mock_for_trait_tokens(cx: &mut ExtCtxt,
                                       sp: Span, mock_ident: Ident,
                                       mut parser: Parser) -> Box<MacResult + 'static> {
-    let trait_mod_path = match parser.token {
-        token::Ident(id) if id.name == keywords::SelfValue.name() => {
-            parser.bump();
-            None
-        },
-        _ => match parser.parse_path(PathStyle::Mod) {
-            Ok(path) => Some(path),
+    let mut trait_items = Vec::new();
+    loop {
+        match parse_trait_definitions(cx, sp, &mut parser) {
+            Some(items) => trait_items.extend(items),
+            None => return DummyResult::any(sp),
+        }
+        if !parser.eat(&Token::Comma) || parser.token == Token::Eof {
+            break;
+        }
+    }
+    if parser.token != Token::Eof {
+        cx.span_err(parser.span, "Comma expected after trait definition");
+        return DummyResult::any(sp);
+    }
+
+    let mut traits = Vec::new();
+    for &(ref trait_path, ref item) in &trait_items {
+        match mocked_trait(cx, sp, trait_path.clone(), item) {
+            Some(mocked) => traits.push(mocked),
+            None => return DummyResult::any(sp),
+        }
+    }
+
+    let generated_items = generate_mock_for_macro(cx, sp, mock_ident, &traits);
+    MacEager::items(SmallVector::many(generated_items))
+}
+
+/// Generate mock declared with `mock!` or `mock_from_file!`.
+///
+/// It isn't known whether trait is defined in current crate, and
+/// `Mocked` can't be implemented for trait objects of other crates,
+/// so it is implemented only for traits marked with `#[mock(local)]`.
+/// Implementation is also conflicting when trait is mocked twice.
+fn generate_mock_for_macro(cx: &mut ExtCtxt, sp: Span, mock_ident: Ident,
+                           traits: &[MockedTrait]) -> Vec<P<Item>> {
+    if traits[0].options.name.is_some() {
+        cx.span_err(sp, "mock name is given by macro argument, `name` option isn't allowed");
+    }
+    let cfg_attr = traits[0].options.cfg.as_ref().map(|cfg| cx.attribute(sp, cfg.clone()));
+
+    let generated_items = generate_mock_for_traits(cx, sp, mock_ident, traits, false);
+    generated_items.into_iter().map(|item| {
+        let item = match cfg_attr {
+            Some(ref cfg_attr) => item.map(|mut it| {
+                it.attrs.push(cfg_attr.clone());
+                it
+            }),
+            None => item,
+        };
+        debug_item(&item);
+        item
+    }).collect()
+}
+
+/// Parse trait module path (may be `self`) and trait definitions
+/// separated by comma. Returns full paths to traits and their definitions.
+fn parse_trait_definitions(cx: &mut ExtCtxt, sp: Span, parser: &mut Parser) -> Option<Vec<(Path, P<Item>)>> {
+    let trait_mod_path = match parse_trait_mod_path(parser) {
+        Ok(path) => path,
+        Err(mut err) => {
+            err.emit();
+            return None
+        }
+    };
+
+    if !parser.eat(&Token::Comma) {
+        cx.span_err(parser.span, "Comma expected after module path");
+        return None
+    }
+
+    let mut items = Vec::new();
+    loop {
+        match parser.parse_item() {
+            Ok(Some(item)) => {
+                let mut trait_path = match trait_mod_path {
+                    Some(ref path) => path.clone(),
+                    None => create_path(sp),
+                };
+                trait_path.segments.push(create_path_segment(item.ident));
+                items.push((trait_path, item));
+            }
+
+            Ok(None) => {
+                cx.span_err(sp, "Trait definition expected");
+                return None;
+            },
+
             Err(mut err) => {
                 err.emit();
-                return DummyResult::any(sp)
+                return None;
             }
         }
+
+        // Next trait from the same module.
+        if !(parser.token.is_keyword(keywords::Trait) || parser.token.is_keyword(keywords::Pub) ||
+             parser.token == Token::Pound) {
+            break;
+        }
+    }
+    Some(items)
+}
+
+/// Parse trait module path, `None` is returned for `self`.
+fn parse_trait_mod_path<'a>(parser: &mut Parser<'a>) -> PResult<'a, Option<Path>> {
+    match parser.token {
+        token::Ident(id) if id.name == keywords::SelfValue.name() => {
+            parser.bump();
+            Ok(None)
+        },
+        _ => parser.parse_path(PathStyle::Mod).map(Some),
+    }
+}
+
+fn parse_mock_from_file_args<'a>(parser: &mut Parser<'a>)
+        -> PResult<'a, (Ident, Option<Path>, String, Vec<Ident>)> {
+    let mock_ident = try!(parse_macro_args(parser));
+    let trait_mod_path = try!(parse_trait_mod_path(parser));
+    try!(parser.expect(&Token::Comma));
+    let (file, _) = try!(parser.parse_str());
+    try!(parser.expect(&Token::Comma));
+    let mut trait_idents = vec![try!(parser.parse_ident())];
+    while parser.eat(&Token::Comma) && parser.token != Token::Eof {
+        trait_idents.push(try!(parser.parse_ident()));
+    }
+    try!(parser.expect(&Token::Eof));
+    Ok((mock_ident, trait_mod_path, (&*file.as_str()).to_owned(), trait_idents))
+}
+
+/// Generate mock for traits defined in given source file, so that
+/// trait definitions needn't be copied into `mock!`:
+/// ```
+/// mock_from_file!(AirConditionerMock, air, "../air/src/context/mod.rs", AirConditioner)
+/// ```
+/// Path to source file is relative to file containing macro invocation,
+/// like for `include!`. Several traits, e.g. mocked one and its supertraits,
+/// may be listed, they are searched in all inline modules of file.
+pub fn generate_mock_from_file<'cx>(cx: &'cx mut ExtCtxt, sp: Span, args: &[TokenTree]) -> Box<MacResult + 'cx> {
+    let mut parser = cx.new_parser_from_tts(args);
+    let (mock_ident, trait_mod_path, file, trait_idents) = match parse_mock_from_file_args(&mut parser) {
+        Ok(args) => args,
+        Err(mut err) => {
+            err.cancel();
+            cx.span_err(sp, "Mock identifier, trait module (may be `self`), path to source file
+                             and trait names separated by comma are expected, example usage:
+                             mock_from_file!(FooMock, ::path::to::foo::module, \"../foo/src/lib.rs\", Foo)");
+            return DummyResult::any(sp);
+        },
     };
 
-    if !parser.eat(&Token::Comma) {
-        cx.span_err(parser.span, "Comma expected after module path");
-        return DummyResult::any(sp)
+    let current_file = std::path::PathBuf::from(cx.codemap().span_to_filename(sp));
+    let path = match current_file.parent() {
+        Some(dir) => dir.join(&file),
+        None => std::path::PathBuf::from(&file),
+    };
+    if !path.is_file() {
+        cx.span_err(sp, &format!("can't find source file `{}`", path.display()));
+        return DummyResult::any(sp);
     }
+    // File is parsed within session of compiler, so it is loaded into
+    // session's codemap. Like for `include_str!`, which adds file to codemap
+    // for this purpose, it makes compiler list file in dependency info,
+    // so crate is rebuilt when file changes.
+    let krate = match parse::parse_crate_from_file(&path, cx.parse_sess) {
+        Ok(krate) => krate,
+        Err(mut err) => {
+            err.emit();
+            return DummyResult::any(sp);
+        },
+    };
 
-    match parser.parse_item() {
-        Ok(Some(item)) => {
-            match item.node {
-                ItemKind::Trait(unsafety, ref generics, ref param_bounds, ref trait_subitems) => {
-                    if unsafety != Unsafety::Normal {
-                        cx.span_err(sp, "Unsafe traits are not supported yet");
-                        return DummyResult::any(sp);
-                    }
+    let mut traits = Vec::new();
+    for &trait_ident in &trait_idents {
+        let item = match find_trait(&krate.module.items, trait_ident.name) {
+            Some(item) => item,
+            None => {
+                cx.span_err(sp, &format!("there is no trait `{}` in `{}`", trait_ident, path.display()));
+                return DummyResult::any(sp);
+            },
+        };
+        let mut trait_path = match trait_mod_path {
+            Some(ref path) => path.clone(),
+            None => create_path(sp),
+        };
+        trait_path.segments.push(create_path_segment(trait_ident));
+        match mocked_trait(cx, sp, trait_path, item) {
+            Some(mocked) => traits.push(mocked),
+            None => return DummyResult::any(sp),
+        }
+    }
 
-                    if generics.is_parameterized() {
-                        cx.span_err(sp, "Parametrized traits are not supported yet");
-                        return DummyResult::any(sp);
-                    }
+    let generated_items = generate_mock_for_macro(cx, sp, mock_ident, &traits);
+    MacEager::items(SmallVector::many(generated_items))
+}
+
+/// Find trait definition with given name in module or its inline submodules.
+fn find_trait(items: &[P<Item>], name: Symbol) -> Option<&Item> {
+    for item in items {
+        match item.node {
+            ItemKind::Trait(..) if item.ident.name == name => return Some(item),
+            ItemKind::Mod(ref module) => {
+                if let Some(item) = find_trait(&module.items, name) {
+                    return Some(item);
+                }
+            },
+            _ => {},
+        }
+    }
+    None
+}
 
-                    assert!(param_bounds.is_empty());
+/// Options given with `#[mock(...)]` attribute on mocked trait.
+#[derive(Default)]
+struct MockOptions {
+    /// Types of associated types, given with `#[mock(types(Item = "u32"))]`.
+    assoc_types: HashMap<Symbol, P<Ty>>,
+    /// Values of associated constants, given with `#[mock(consts(NAME = "\"foo\""))]`.
+    assoc_consts: HashMap<Symbol, P<Expr>>,
+    /// Provided methods which aren't mocked, so trait's default implementation
+    /// is used, given with `#[mock(default_impl = "describe")]`.
+    default_impls: HashMap<Symbol, Span>,
+    /// Mock struct name, given with `#[mock(name = "FakeAc")]`.
+    name: Option<Ident>,
+    /// Mock struct visibility, given with `#[mock(vis = "pub(crate)")]`.
+    vis: Option<Visibility>,
+    /// Condition for `cfg` attribute put on generated items instead of `test`,
+    /// given with `#[mock(cfg = "any(test, feature = \"mocks\")")]`.
+    cfg: Option<MetaItem>,
+    /// Mocked trait is defined in current crate, so `Mocked` may be
+    /// implemented for it, given with `#[mock(local)]`.
+    local: bool,
+    /// Mock implements `Clone`, clones share expectations, given
+    /// with `#[mock(clone)]`.
+    clone: bool,
+    /// Mock doesn't implement `Debug`, `PartialEq` and `Eq`, so user may
+    /// implement them, given with `#[mock(no_common_traits)]`.
+    no_common_traits: bool,
+}
 
-                    let mut trait_path = match trait_mod_path {
-                        Some(path) => path.clone(),
-                        None => create_path(sp),
-                    };
-                    trait_path.segments.push(create_path_segment(item.ident));
-                    let generated_items = generate_mock_for_trait(cx, sp, mock_ident, &trait_path, trait_subitems, false);
-                    for item in &generated_items {
-                        debug_item(item);
+fn parse_mock_options(cx: &mut ExtCtxt, attrs: &[Attribute]) -> MockOptions {
+    let mut options = MockOptions::default();
+    for attr in attrs.iter().filter(|a| a.check_name("mock")) {
+        attr::mark_used(attr);
+        let items = match attr.meta_item_list() {
+            Some(items) => items,
+            None => {
+                cx.span_err(attr.span, "list of options is expected, like `#[mock(types(Item = \"u32\"))]`");
+                continue;
+            }
+        };
+
+        for item in items {
+            if item.check_name("types") {
+                for (name, value, sp) in parse_name_value_list(cx, item) {
+                    if let Some(ty) = parse_option_str(cx, sp, &value, |p| p.parse_ty()) {
+                        options.assoc_types.insert(name, ty);
+                    }
+                }
+            } else if item.check_name("consts") {
+                for (name, value, sp) in parse_name_value_list(cx, item) {
+                    if let Some(expr) = parse_option_str(cx, sp, &value, |p| p.parse_expr()) {
+                        options.assoc_consts.insert(name, expr);
                     }
-                    MacEager::items(SmallVector::many(generated_items))
-                },
-                _ => {
-                    cx.span_err(sp, "Trait definition expected");
-                    DummyResult::any(sp)
                 }
+            } else if item.check_name("default_impl") {
+                match item.value_str() {
+                    Some(name) => { options.default_impls.insert(name, item.span); },
+                    None => cx.span_err(item.span, "method name is expected, like `default_impl = \"describe\"`"),
+                }
+            } else if item.check_name("name") {
+                match item.value_str() {
+                    Some(name) => options.name = Some(cx.ident_of(&name.as_str())),
+                    None => cx.span_err(item.span, "mock name is expected, like `name = \"FakeFoo\"`"),
+                }
+            } else if item.check_name("vis") {
+                match item.value_str() {
+                    // `Parser::parse_visibility` is private, so visibility
+                    // is parsed as part of item.
+                    Some(vis) => options.vis = parse_option_str(cx, item.span, &format!("{} struct Vis;", vis),
+                                                                |p| p.parse_item())
+                        .and_then(|item| item).map(|item| item.vis.clone()),
+                    None => cx.span_err(item.span, "visibility is expected, like `vis = \"pub(crate)\"`"),
+                }
+            } else if item.check_name("cfg") {
+                match item.value_str() {
+                    Some(cfg) => options.cfg = parse_option_str(cx, item.span, &format!("cfg({})", cfg),
+                                                                |p| p.parse_meta_item()),
+                    None => cx.span_err(item.span, "condition is expected, like `cfg = \"test\"`"),
+                }
+            } else if item.check_name("local") && item.is_word() {
+                options.local = true;
+            } else if item.check_name("clone") && item.is_word() {
+                options.clone = true;
+            } else if item.check_name("no_common_traits") && item.is_word() {
+                options.no_common_traits = true;
+            } else {
+                cx.span_err(item.span, "unknown mock option");
             }
         }
+    }
+    options
+}
 
-        Ok(None) => {
-            cx.span_err(sp, "Trait definition expected");
-            DummyResult::any(sp)
-        },
+/// Parse option like `types(A = "u32", B = "String")`.
+fn parse_name_value_list(cx: &mut ExtCtxt, item: &NestedMetaItem) -> Vec<(Symbol, String, Span)> {
+    let list = match item.meta_item_list() {
+        Some(list) => list,
+        None => {
+            cx.span_err(item.span, "list of `Name = \"value\"` pairs is expected");
+            return vec![];
+        }
+    };
+    list.iter().filter_map(|pair| {
+        match (pair.name(), pair.value_str()) {
+            (Some(name), Some(value)) => Some((name, value.as_str().to_string(), pair.span)),
+            _ => {
+                cx.span_err(pair.span, "`Name = \"value\"` is expected");
+                None
+            }
+        }
+    }).collect()
+}
 
+/// Parse string value of mock option as Rust code, like type or expression.
+fn parse_option_str<'a, T, F>(cx: &'a ExtCtxt, sp: Span, value: &str, parse: F) -> Option<T>
+        where F: FnOnce(&mut Parser<'a>) -> PResult<'a, T> {
+    let mut parser = parse::new_parser_from_source_str(cx.parse_sess,
+                                                       "<mock option>".to_owned(),
+                                                       value.to_owned());
+    match parse(&mut parser) {
+        Ok(result) => Some(result),
         Err(mut err) => {
             err.emit();
-            DummyResult::any(sp)
+            cx.span_err(sp, "can't parse mock option value");
+            None
         }
     }
 }
 
-struct GeneratedMethods {
-    trait_impl_method: ImplItem,
-    impl_method: ImplItem,
-}
+/// Replaces `Self::Name` and `<Self as Trait>::Name` types with
+/// types given for associated types of mocked trait.
+///
+/// `Self::Item` can't be used in inherent impl of mock struct nor in
+/// nested functions of mocked methods.
+struct AssocTypesFolder<'a> {
+    assoc_types: &'a HashMap<Symbol, P<Ty>>,
+}
+impl<'a> Folder for AssocTypesFolder<'a> {
+    fn fold_ty(&mut self, ty: P<Ty>) -> P<Ty> {
+        let assoc_name = match ty.node {
+            TyKind::Path(None, ref path) if path.segments.len() == 2 &&
+                    path.segments[0].identifier.name == keywords::SelfType.name() => {
+                Some(path.segments[1].identifier.name)
+            },
+            TyKind::Path(Some(ref qself), ref path) => match qself.ty.node {
+                TyKind::Path(None, ref self_path) if self_path.segments.len() == 1 &&
+                        self_path.segments[0].identifier.name == keywords::SelfType.name() => {
+                    path.segments.last().map(|s| s.identifier.name)
+                },
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(ty) = assoc_name.and_then(|name| self.assoc_types.get(&name)) {
+            return ty.clone();
+        }
+        fold::noop_fold_ty(ty, self)
+    }
+
+    fn fold_mac(&mut self, mac: Mac) -> Mac {
+        fold::noop_fold_mac(mac, self)
+    }
+}
+
+/// Replaces all lifetimes, including elided ones, with `'static`.
+///
+/// It is used for result types and where clauses of call matchers,
+/// which live in scenario and can't refer to lifetimes of method call.
+/// Result of `'static` type may be returned for any lifetime. Argument
+/// types are never erased, borrowed arguments are described with
+/// `ArgKind` markers instead, see `arg_kind`.
+struct StaticLifetimesFolder {
+    sp: Span,
+}
+impl Folder for StaticLifetimesFolder {
+    fn fold_ty(&mut self, ty: P<Ty>) -> P<Ty> {
+        let sp = self.sp;
+        fold::noop_fold_ty(ty, self).map(|mut ty| {
+            if let TyKind::Rptr(ref mut lifetime, _) = ty.node {
+                if lifetime.is_none() {
+                    *lifetime = Some(static_lifetime(sp));
+                }
+            }
+            ty
+        })
+    }
+
+    fn fold_lifetime(&mut self, _: Lifetime) -> Lifetime {
+        static_lifetime(self.sp)
+    }
+
+    fn fold_mac(&mut self, mac: Mac) -> Mac {
+        fold::noop_fold_mac(mac, self)
+    }
+}
+
+fn static_lifetime(sp: Span) -> Lifetime {
+    Lifetime { id: DUMMY_NODE_ID, span: sp, name: Symbol::intern("'static") }
+}
+
+fn erase_lifetimes(sp: Span, ty: &P<Ty>) -> P<Ty> {
+    StaticLifetimesFolder { sp: sp }.fold_ty(ty.clone())
+}
+
+/// Collects spans of all lifetimes other than `'static`, including
+/// elided lifetimes of references.
+struct NonStaticLifetimesFolder {
+    spans: Vec<Span>,
+}
+impl Folder for NonStaticLifetimesFolder {
+    fn fold_ty(&mut self, ty: P<Ty>) -> P<Ty> {
+        match ty.node {
+            // Lifetimes of function pointer are bound by its signature.
+            TyKind::BareFn(..) => return ty,
+            TyKind::Rptr(None, _) => self.spans.push(ty.span),
+            _ => (),
+        }
+        fold::noop_fold_ty(ty, self)
+    }
+
+    fn fold_path_parameters(&mut self, params: PathParameters) -> PathParameters {
+        match params {
+            // The same for `Fn(&T)` traits.
+            PathParameters::Parenthesized(..) => params,
+            _ => fold::noop_fold_path_parameters(params, self),
+        }
+    }
+
+    fn fold_lifetime(&mut self, lifetime: Lifetime) -> Lifetime {
+        if &*lifetime.name.as_str() != "'static" {
+            self.spans.push(lifetime.span);
+        }
+        lifetime
+    }
+
+    fn fold_mac(&mut self, mac: Mac) -> Mac {
+        fold::noop_fold_mac(mac, self)
+    }
+}
+
+/// Check that arguments borrow nothing except top-level references.
+///
+/// Borrowed arguments are given to actions and predicates for any
+/// lifetime, which can be expressed for `&T` and `&mut T` arguments only.
+/// All errors are reported to `cx`.
+fn check_arg_lifetimes(cx: &mut ExtCtxt, args: &[Arg]) -> bool {
+    let mut folder = NonStaticLifetimesFolder { spans: vec![] };
+    for arg in args {
+        match arg.ty.node {
+            TyKind::Rptr(_, ref mut_ty) => folder.fold_ty(mut_ty.ty.clone()),
+            _ => folder.fold_ty(arg.ty.clone()),
+        };
+    }
+    for &span in &folder.spans {
+        cx.span_err(span, "only arguments themselves may be references, \
+                           lifetimes inside of argument types must be `'static`");
+    }
+    folder.spans.is_empty()
+}
+
+/// Returns type of value matched by argument matchers and name of
+/// `ArgKind` marker telling how argument is passed, for example
+/// `str` and `ByRef` for `&str` argument.
+fn arg_kind(ty: &P<Ty>) -> (P<Ty>, &'static str) {
+    match ty.node {
+        TyKind::Rptr(_, ref mut_ty) => match mut_ty.mutbl {
+            Mutability::Immutable => (mut_ty.ty.clone(), "ByRef"),
+            Mutability::Mutable => (mut_ty.ty.clone(), "ByMutRef"),
+        },
+        _ => (ty.clone(), "ByValue"),
+    }
+}
+
+/// Generate type arguments of `CallMatchN` and `ExpectationBuilderN`:
+/// types matched by argument matchers, result type and, if some
+/// argument is reference, `ArgKind` markers of all arguments.
+fn call_match_type_args(cx: &mut ExtCtxt, sp: Span, args: &[Arg], return_type: &Ty) -> Vec<P<Ty>> {
+    let kinds: Vec<_> = args.iter().map(|arg| arg_kind(&arg.ty)).collect();
+    let mut type_args: Vec<P<Ty>> = kinds.iter().map(|&(ref ty, _)| ty.clone()).collect();
+    type_args.push(erase_lifetimes(sp, &P(return_type.clone())));
+    if kinds.iter().any(|&(_, kind)| kind != "ByValue") {
+        for &(_, kind) in &kinds {
+            type_args.push(cx.ty_path(cx.path_global(sp, vec![cx.ident_of("mockers"), cx.ident_of(kind)])));
+        }
+    }
+    type_args
+}
+
+struct GeneratedMethods {
+    trait_impl_method: ImplItem,
+    impl_method: ImplItem,
+    expect_method: ImplItem,
+    /// Inherent method which `extern "C"` callback forwards calls to.
+    forward_method: Option<ImplItem>,
+    /// Arguments struct and `*_call_with` method, if they can be
+    /// generated for method.
+    call_with_items: Vec<P<Item>>,
+}
+
+/// Attributes of trait item which are copied to all items generated for it:
+/// conditional compilation, lint levels and deprecation. Deprecation is
+/// removed from trait impl items later.
+///
+/// Generated items of deprecated method use each other, so they also
+/// allow use of deprecated items.
+fn propagated_attrs(cx: &ExtCtxt, sp: Span, attrs: &[Attribute]) -> Vec<Attribute> {
+    const NAMES: &'static [&'static str] = &["cfg", "cfg_attr", "allow", "warn", "deny", "forbid", "deprecated"];
+    let mut propagated: Vec<Attribute> =
+        attrs.iter().filter(|attr| NAMES.iter().any(|&name| attr.check_name(name))).cloned().collect();
+    if attrs.iter().any(|attr| attr.check_name("deprecated")) {
+        propagated.push(cx.attribute(sp, cx.meta_list(sp, Symbol::intern("allow"),
+                                                      vec![cx.meta_list_item_word(sp, Symbol::intern("deprecated"))])));
+    }
+    propagated
+}
+
+/// Mocked trait definition together with path to it.
+struct MockedTrait<'a> {
+    path: Path,
+    generics: &'a Generics,
+    bounds: &'a [TyParamBound],
+    members: &'a [TraitItem],
+    options: MockOptions,
+    /// Inherent impl of struct is mocked instead of trait, contains
+    /// visibility of each method.
+    inherent_vis: Option<HashMap<Symbol, Visibility>>,
+}
+
+/// Check that item is trait definition which may be mocked.
+fn mocked_trait<'a>(cx: &mut ExtCtxt, sp: Span, path: Path, item: &'a Item) -> Option<MockedTrait<'a>> {
+    match item.node {
+        ItemKind::Trait(unsafety, ref generics, ref bounds, ref members) => {
+            if unsafety != Unsafety::Normal {
+                cx.span_err(sp, "Unsafe traits are not supported yet");
+                return None;
+            }
+
+            if !generics.lifetimes.is_empty() {
+                cx.span_err(sp, "Traits with lifetime parameters are not supported yet");
+                return None;
+            }
+
+            Some(MockedTrait {
+                path: path,
+                generics: generics,
+                bounds: &bounds[..],
+                members: &members[..],
+                options: parse_mock_options(cx, &item.attrs),
+                inherent_vis: None,
+            })
+        },
+        _ => {
+            cx.span_err(sp, "Trait definition expected");
+            None
+        }
+    }
+}
+
+/// Supertraits which don't need to be mocked, mock struct
+/// gets implementation for them.
+const MARKER_TRAITS: &'static [&'static str] = &[
+    "Sized", "Debug", "Clone", "PartialEq", "Eq", "Hash",
+];
+
+/// Generate mock struct and all implementations for it.
+///
+/// First trait is mocked one, the rest are definitions of its supertraits
+/// or other traits mocked together with it. All of them are implemented by
+/// single mock struct, so expectations for methods of all traits are set on
+/// the same mock object.
+///
+/// Mock for generic trait `Foo<T>` is generic too, `FooMock<T>` implements
+/// `Foo<T>` for every `T` satisfying trait bounds.
+///
+/// Associated types and constants of mocked trait are taken from
+/// `#[mock(types(...), consts(...))]` options or from defaults given
+/// in trait definition (it is usual for `mock!`).
+fn generate_mock_for_traits(cx: &mut ExtCtxt, sp: Span, mock_ident: Ident,
+                            traits: &[MockedTrait], local: bool) -> Vec<P<Item>> {
+    let main_trait = &traits[0];
+    let local = local || main_trait.options.local;
+    let trait_generics = main_trait.generics;
+    let mut impl_methods = Vec::new();
+    let mut forward_methods = Vec::new();
+    let mut trait_impl_methods: Vec<Vec<ImplItem>> = traits.iter().map(|_| Vec::new()).collect();
+    // `Mocked` is implemented for trait object type, so it is only
+    // possible for object-safe traits.
+    let mut object_safe = true;
+    let mut has_callbacks = false;
+    let mut call_with_items = Vec::new();
+    // Mock methods are named after trait methods, so each name may be
+    // used by single trait only.
+    let mut method_traits: HashMap<Symbol, String> = HashMap::new();
+
+    for supertrait in &traits[1..] {
+        if !supertrait.generics.ty_params.is_empty() {
+            cx.span_err(sp, &format!("only first mocked trait may be generic, `{}` is generic",
+                                     pprust::path_to_string(&supertrait.path)));
+            return vec![];
+        }
+    }
+
+    // Associated types must be known before methods are generated,
+    // because they may be used in method signatures.
+    let mut assoc_types = HashMap::new();
+    let mut assoc_type_bindings = Vec::new();
+    for (mocked, trait_impl_methods) in traits.iter().zip(trait_impl_methods.iter_mut()) {
+        for member in mocked.members.iter() {
+            if let TraitItemKind::Type(_, ref default) = member.node {
+                let ty = match mocked.options.assoc_types.get(&member.ident.name).or(default.as_ref()) {
+                    Some(ty) => ty.clone(),
+                    None => {
+                        cx.span_err(member.span,
+                                    &format!("type must be given for associated type `{}`, \
+                                              use `#[mock(types({} = \"...\"))]`",
+                                             member.ident, member.ident));
+                        continue;
+                    }
+                };
+                assoc_types.insert(member.ident.name, ty.clone());
+                assoc_type_bindings.push(TypeBinding {
+                    id: DUMMY_NODE_ID,
+                    ident: member.ident,
+                    ty: ty.clone(),
+                    span: sp,
+                });
+                trait_impl_methods.push(ImplItem {
+                    id: DUMMY_NODE_ID,
+                    ident: member.ident,
+                    vis: Visibility::Inherited,
+                    attrs: propagated_attrs(cx, sp, &member.attrs),
+                    node: ImplItemKind::Type(ty),
+                    span: sp,
+                    defaultness: Defaultness::Final,
+                });
+            }
+        }
+    }
+
+    for (mocked, trait_impl_methods) in traits.iter().zip(trait_impl_methods.iter_mut()) {
+        let options = &mocked.options;
+        let members = mocked.members;
+        let mock_key = format!("{}::{}", mock_ident, pprust::path_to_string(&mocked.path));
+        for member in members.iter() {
+            if let TraitItemKind::Const(ref ty, ref default) = member.node {
+                // Traits with associated constants can't be made into objects.
+                object_safe = false;
+
+                let value = match options.assoc_consts.get(&member.ident.name).or(default.as_ref()) {
+                    Some(value) => value.clone(),
+                    None => {
+                        cx.span_err(member.span,
+                                    &format!("value must be given for associated constant `{}`, \
+                                              use `#[mock(consts({} = \"...\"))]`",
+                                             member.ident, member.ident));
+                        continue;
+                    }
+                };
+                trait_impl_methods.push(ImplItem {
+                    id: DUMMY_NODE_ID,
+                    ident: member.ident,
+                    vis: Visibility::Inherited,
+                    attrs: propagated_attrs(cx, sp, &member.attrs),
+                    node: ImplItemKind::Const(ty.clone(), value),
+                    span: sp,
+                    defaultness: Defaultness::Final,
+                });
+            } else if let TraitItemKind::Method(ref sig, ref opt_body) = member.node {
+                // Method isn't implemented by mock at all, so default body
+                // calls other methods of mock.
+                if options.default_impls.contains_key(&member.ident.name) {
+                    if opt_body.is_none() {
+                        cx.span_err(member.span,
+                                    &format!("method `{}` has no default implementation", member.ident));
+                    }
+                    continue;
+                }
+
+                if sig.unsafety != Unsafety::Normal {
+                    cx.span_err(member.span, "unsafe trait methods are not supported");
+                    continue;
+                }
+                if sig.constness.node != Constness::NotConst {
+                    cx.span_err(member.span, "const trait methods are not supported");
+                    continue;
+                }
+                if sig.abi != Abi::Rust {
+                    if !is_callback(sig) {
+                        cx.span_err(member.span,
+                                    "non-Rust ABIs are supported only for static methods \
+                                     taking `*mut c_void` user data as first argument");
+                        continue;
+                    }
+                    has_callbacks = true;
+                }
+                let trait_name = pprust::path_to_string(&mocked.path);
+                match method_traits.insert(member.ident.name, trait_name.clone()) {
+                    Some(ref other_name) if *other_name != trait_name => {
+                        cx.span_err(member.span,
+                                    &format!("method `{}` is defined by both `{}` and `{}`, \
+                                              traits with methods of the same name can't be mocked together",
+                                             member.ident, other_name, trait_name));
+                        continue;
+                    },
+                    _ => (),
+                }
+                // Generic and static methods can't be called on trait object.
+                if !sig.generics.ty_params.is_empty() || sig.decl.get_self().is_none() {
+                    object_safe = false;
+                }
+
+                let sig = fold::noop_fold_method_sig(sig.clone(),
+                                                     &mut AssocTypesFolder { assoc_types: &assoc_types });
+                // Arguments struct is visible like mock, or like mocked method of inherent impl.
+                let args_vis = match mocked.inherent_vis {
+                    Some(ref visibilities) =>
+                        visibilities.get(&member.ident.name).cloned().unwrap_or(Visibility::Inherited),
+                    None => main_trait.options.vis.clone().unwrap_or(Visibility::Public),
+                };
+                if let Some(mut methods) = generate_trait_methods(cx, member.span, mock_ident, &mock_key,
+                                                                  trait_generics, member.ident, &sig,
+                                                                  args_vis) {
+                    // Method under `#[cfg]` must be mocked only when it exists.
+                    let attrs = propagated_attrs(cx, sp, &member.attrs);
+                    methods.impl_method.attrs.extend(attrs.iter().cloned());
+                    methods.expect_method.attrs.extend(attrs.iter().cloned());
+                    methods.trait_impl_method.attrs.extend(attrs.iter().cloned());
+                    impl_methods.push(methods.impl_method);
+                    impl_methods.push(methods.expect_method);
+                    trait_impl_methods.push(methods.trait_impl_method);
+                    if let Some(mut forward_method) = methods.forward_method {
+                        forward_method.attrs.extend(attrs.iter().cloned());
+                        forward_methods.push(forward_method);
+                    }
+                    call_with_items.extend(methods.call_with_items.into_iter().map(|item| {
+                        item.map(|mut it| { it.attrs.extend(attrs.iter().cloned()); it })
+                    }));
+                }
+            } else if let TraitItemKind::Macro(..) = member.node {
+                cx.span_err(member.span, "macros in trait definition are not supported");
+            }
+        }
+        for (name, &span) in &options.default_impls {
+            let is_method = members.iter().any(|m| {
+                m.ident.name == *name && if let TraitItemKind::Method(..) = m.node { true } else { false }
+            });
+            if !is_method {
+                cx.span_err(span, &format!("there is no method `{}` in mocked trait", name));
+            }
+        }
+    }
+
+    // Supertraits are either marker ones or must be mocked too.
+    let mut marker_traits: Vec<String> = Vec::new();
+    for mocked in traits {
+        for bound in mocked.bounds {
+            let path = match *bound {
+                TyParamBound::TraitTyParamBound(ref poly_trait_ref, TraitBoundModifier::None) =>
+                    &poly_trait_ref.trait_ref.path,
+                // `?Sized` and lifetime bounds.
+                _ => continue,
+            };
+            let name = match path.segments.last() {
+                Some(segment) => segment.identifier.name,
+                None => continue,
+            };
+            if ["Send", "Sync"].contains(&&*name.as_str()) {
+                // Mock holds `Rc` to scenario, so it can't be sent
+                // to or shared with another thread.
+                cx.span_err(path.span,
+                            &format!("`{}` supertrait of `{}` can't be mocked, mocks can't be used \
+                                      outside of thread where scenario lives",
+                                     name, pprust::path_to_string(&mocked.path)));
+            } else if MARKER_TRAITS.contains(&&*name.as_str()) {
+                // These traits require `Self: Sized` or use `Self` type.
+                if &*name.as_str() != "Debug" {
+                    object_safe = false;
+                }
+                if !marker_traits.iter().any(|n| *n == *name.as_str()) {
+                    marker_traits.push(name.as_str().to_string());
+                }
+            } else if !traits[1..].iter().any(|t| t.path.segments.last().map(|s| s.identifier.name) == Some(name)) {
+                cx.span_err(sp, &format!("supertrait `{}` must be mocked too, use `mock!` and give \
+                                          its definition after definition of `{}`",
+                                         pprust::path_to_string(path),
+                                         pprust::path_to_string(&mocked.path)));
+            }
+        }
+    }
+    // Mocks may be printed and compared by identity, and cloned if asked,
+    // so code requiring these bounds may be tested.
+    let mut common_traits = if main_trait.options.no_common_traits {
+        vec![]
+    } else {
+        vec!["Debug", "PartialEq", "Eq"]
+    };
+    if main_trait.options.clone {
+        common_traits.push("Clone");
+    }
+    for name in common_traits {
+        if !marker_traits.iter().any(|n| n == name) {
+            marker_traits.push(name.to_string());
+        }
+    }
+
+    // Other traits which aren't supertraits are mocked together with the
+    // first one, mock is named after all of them, like `Reader+Writer`.
+    let extra_class_names: Vec<String> = traits[1..].iter().filter(|mocked| {
+        let name = mocked.path.segments.last().map(|s| s.identifier.name);
+        !traits.iter().any(|t| t.bounds.iter().any(|b| match *b {
+            TyParamBound::TraitTyParamBound(ref poly_trait_ref, _) =>
+                poly_trait_ref.trait_ref.path.segments.last().map(|s| s.identifier.name) == name,
+            _ => false,
+        }))
+    }).map(|mocked| format!("+{}", pprust::path_to_string(&mocked.path))).collect();
+
+    // Trait type parameters are used by mock struct and its implementations.
+    // Expectations are stored in scenario, so they must be `'static`.
+    // Trait implementation gets the same bounds, because static methods
+    // need `Self: Mock` to find out mocked class name.
+    let static_generics = add_static_bounds(cx, sp, trait_generics);
+    let trait_params: Vec<Ident> = trait_generics.ty_params.iter().map(|p| p.ident).collect();
+    let trait_params_tys: Vec<P<Ty>> = trait_params.iter().map(|&p| cx.ty_ident(sp, p)).collect();
+    let trait_path = path_with_params(cx, sp, &main_trait.path, trait_params_tys.clone());
+    let mock_ty = cx.ty_path(cx.path_all(sp, false, vec![mock_ident], vec![], trait_params_tys.clone(), vec![]));
+    // Handle gets the same methods for creating expectations, they use just
+    // `scenario` and `mock_id` fields. Static methods aren't bound to mock
+    // instance, so they are available on mock type only.
+    let handle_methods: Vec<ImplItem> = impl_methods.iter().filter(|method| match method.node {
+        ImplItemKind::Method(ref sig, _) => sig.decl.get_self().is_some(),
+        _ => false,
+    }).cloned().collect();
+    // Callbacks forward calls to mock only.
+    impl_methods.extend(forward_methods);
+    let mut trait_impl_items: Vec<P<Item>> = Vec::new();
+    for (i, (mocked, methods)) in traits.iter().zip(trait_impl_methods.into_iter()).enumerate() {
+        // Mocked methods of inherent impl are inherent methods of mock.
+        if let Some(ref visibilities) = mocked.inherent_vis {
+            impl_methods.extend(methods.into_iter().map(|mut method| {
+                method.vis = visibilities.get(&method.ident.name).cloned().unwrap_or(Visibility::Inherited);
+                method
+            }));
+            continue;
+        }
+        // Deprecation of trait impl items is rejected by compiler as useless.
+        let methods = methods.into_iter().map(|mut item| {
+            item.attrs.retain(|attr| !attr.check_name("deprecated"));
+            item
+        }).collect();
+        let path = if i == 0 { trait_path.clone() } else { mocked.path.clone() };
+        trait_impl_items.push(cx.item(sp,
+                                      mock_ident,
+                                      vec![],
+                                      ItemKind::Impl(Unsafety::Normal,
+                                                     ImplPolarity::Positive,
+                                                     static_generics.clone(),
+                                                     Some(cx.trait_ref(path)),
+                                                     mock_ty.clone(),
+                                                     methods)));
+    }
+    let impl_item = cx.item(sp,
+                            mock_ident,
+                            vec![],
+                            ItemKind::Impl(Unsafety::Normal,
+                                           ImplPolarity::Positive,
+                                           static_generics.clone(),
+                                           None,
+                                           mock_ty.clone(),
+                                           impl_methods));
+
+    // Type name of generic trait mock includes names of type parameters,
+    // like `Foo<u32>`, class name doesn't.
+    let trait_name = pprust::path_to_string(&path_with_params(cx, sp, &trait_path, vec![]));
+    let extra_class_names = extra_class_names.concat();
+    let mocked_class_name = format!("{}{}", trait_name, extra_class_names);
+    let mocked_type_name_expr = if trait_params.is_empty() {
+        quote_expr!(cx, $mocked_class_name.to_owned())
+    } else {
+        let format_str = format!("{}<{}>{}", trait_name,
+                                 std::iter::repeat("{}").take(trait_params.len()).join(", "),
+                                 extra_class_names);
+        let type_names: Vec<P<Expr>> = trait_params.iter().map(|&p| {
+            quote_expr!(cx, ::mockers::type_name::<$p>())
+        }).collect();
+        let type_names_sep = comma_sep(&type_names);
+        quote_expr!(cx, format!($format_str, $type_names_sep))
+    };
 
-fn generate_mock_for_trait(cx: &mut ExtCtxt, sp: Span,
-                           mock_ident: Ident, trait_path: &Path,
-                           members: &[TraitItem], local: bool) -> Vec<P<Item>> {
-    let mut impl_methods = Vec::with_capacity(members.len());
-    let mut trait_impl_methods = Vec::with_capacity(members.len());
+    // Trait object type must specify all associated types.
+    let trait_object_path = path_with_params_and_bindings(cx, sp, &trait_path, trait_params_tys.clone(),
+                                                          assoc_type_bindings);
+    let mocked_impl_item = quote_item!(cx,
+        impl ::mockers::Mocked for &'static $trait_object_path {
+            type MockImpl = $mock_ty;
+        }
+    ).unwrap();
+    let mocked_impl_item = set_item_generics(mocked_impl_item, &static_generics);
 
-    for member in members.iter() {
-        if let TraitItemKind::Method(ref sig, ref _opt_body) = member.node {
-            if sig.unsafety != Unsafety::Normal {
-                cx.span_err(member.span, "unsafe trait methods are not supported");
-                continue;
-            }
-            if sig.constness.node != Constness::NotConst {
-                cx.span_err(member.span, "const trait methods are not supported");
-                continue;
-            }
-            if sig.abi != Abi::Rust {
-                cx.span_err(member.span, "non-Rust ABIs for trait methods are not supported");
-                continue;
-            }
-            if sig.generics.is_parameterized() {
-                cx.span_err(member.span, "parametrized trait methods are not supported");
-                continue;
+    let handle_ident = cx.ident_of(&format!("{}Handle", mock_ident));
+    let handle_ty = cx.ty_path(cx.path_all(sp, false, vec![handle_ident], vec![], trait_params_tys.clone(), vec![]));
+    // Mocked method named `handle` would be shadowed by inherent one,
+    // `{Mock}Handle::new` must be used then.
+    let has_handle_method = traits.iter().any(|mocked| {
+        mocked.members.iter().any(|member| &*member.ident.name.as_str() == "handle")
+    });
+    let handle_method_item = quote_item!(cx,
+        impl $mock_ty {
+            #[allow(dead_code)]
+            pub fn handle(&self) -> $handle_ty {
+                $handle_ident::new(self)
             }
+        }
+    ).unwrap();
+    let handle_impl_item = cx.item(sp,
+                                   handle_ident,
+                                   vec![],
+                                   ItemKind::Impl(Unsafety::Normal,
+                                                  ImplPolarity::Positive,
+                                                  static_generics.clone(),
+                                                  None,
+                                                  handle_ty.clone(),
+                                                  handle_methods));
 
-            if let Some(methods) = generate_trait_methods(cx, member.span, member.ident, &sig.decl) {
-                impl_methods.push(methods.impl_method);
-                trait_impl_methods.push(methods.trait_impl_method);
+    // Struct of inherent impl mock is generated from `#[mockable]` struct,
+    // each `#[mockable]` impl adds its methods only.
+    let inherent = main_trait.inherent_vis.is_some();
+    let mut items = if inherent {
+        vec![]
+    } else {
+        generate_mock_struct(cx, sp, mock_ident, trait_generics, main_trait.options.vis.clone(),
+                             mocked_class_name, mocked_type_name_expr)
+    };
+    items.push(impl_item);
+    items.extend(trait_impl_items);
+    items.push(set_item_generics(handle_impl_item, &static_generics));
+    if !inherent {
+        if !has_handle_method {
+            items.push(set_item_generics(handle_method_item, &static_generics));
+        }
+        for name in &marker_traits {
+            if let Some(item) = generate_marker_impl(cx, mock_ident, &mock_ty, name) {
+                items.push(set_item_generics(item, &static_generics));
             }
-        } else {
-            cx.span_err(member.span, "trait constants and associated types are not supported yet");
         }
     }
+    items.extend(call_with_items);
+    // `Mocked` impl is public, so it can't expose mock which isn't.
+    let public = match main_trait.options.vis {
+        None | Some(Visibility::Public) => true,
+        _ => false,
+    };
+    if local && object_safe && public {
+        items.push(mocked_impl_item);
+    }
+    if has_callbacks {
+        // Pointer to be passed to C code together with callbacks.
+        let user_data_item = quote_item!(cx,
+            impl $mock_ty {
+                #[allow(dead_code)]
+                pub fn user_data(&self) -> *mut ::std::os::raw::c_void {
+                    self as *const Self as *mut ::std::os::raw::c_void
+                }
+            }
+        ).unwrap();
+        items.push(set_item_generics(user_data_item, &static_generics));
+    }
+    items
+}
+
+/// Generate mock struct, its `Mock` implementation and handle struct.
+///
+/// Mock without `vis` option is public, handle always has the same
+/// visibility as mock.
+fn generate_mock_struct(cx: &mut ExtCtxt, sp: Span, mock_ident: Ident, generics: &Generics,
+                        vis: Option<Visibility>, mocked_class_name: String,
+                        mocked_type_name_expr: P<Expr>) -> Vec<P<Item>> {
+    let static_generics = add_static_bounds(cx, sp, generics);
+    let params: Vec<Ident> = generics.ty_params.iter().map(|p| p.ident).collect();
+    let params_tys: Vec<P<Ty>> = params.iter().map(|&p| cx.ty_ident(sp, p)).collect();
+    let mock_ty = cx.ty_path(cx.path_all(sp, false, vec![mock_ident], vec![], params_tys.clone(), vec![]));
+    let phantom_tys: Vec<P<Ty>> = params.iter().map(|&p| {
+        cx.ty_ptr(sp, cx.ty_ident(sp, p), Mutability::Immutable)
+    }).collect();
+    let phantom_ty = cx.ty(sp, TyKind::Tup(phantom_tys));
 
     let struct_item = quote_item!(cx,
         pub struct $mock_ident {
             scenario: ::std::rc::Rc<::std::cell::RefCell<::mockers::ScenarioInternals>>,
             mock_id: usize,
+            _phantom: ::std::marker::PhantomData<$phantom_ty>,
         }
     ).unwrap();
-    let impl_item = cx.item(sp,
-                            mock_ident,
-                            vec![],
-                            ItemKind::Impl(Unsafety::Normal,
-                                           ImplPolarity::Positive,
-                                           Generics::default(),
-                                           None,
-                                           cx.ty_ident(sp, mock_ident),
-                                           impl_methods));
-    let trait_impl_item = cx.item(sp,
-                                  mock_ident,
-                                  vec![],
-                                  ItemKind::Impl(Unsafety::Normal,
-                                                 ImplPolarity::Positive,
-                                                 Generics::default(),
-                                                 Some(cx.trait_ref(trait_path.clone())),
-                                                 cx.ty_ident(sp, mock_ident),
-                                                 trait_impl_methods));
-
-    let mocked_class_name = pprust::path_to_string(trait_path);
+    let struct_item = set_item_generics(struct_item, generics);
+    let struct_item = match vis {
+        Some(ref vis) => struct_item.map(|mut it| {
+            it.vis = vis.clone();
+            it
+        }),
+        None => struct_item,
+    };
 
     let mock_impl_item = quote_item!(cx,
-        impl ::mockers::Mock for $mock_ident {
+        impl ::mockers::Mock for $mock_ty {
             fn new(id: usize, scenario_int: ::std::rc::Rc<::std::cell::RefCell<::mockers::ScenarioInternals>>) -> Self {
                 $mock_ident {
                     scenario: scenario_int,
                     mock_id: id,
+                    _phantom: ::std::marker::PhantomData,
                 }
             }
 
             fn mocked_class_name() -> &'static str {
                 $mocked_class_name
             }
+
+            fn mocked_type_name() -> String {
+                $mocked_type_name_expr
+            }
         }
     ).unwrap();
+    let mock_impl_item = set_item_generics(mock_impl_item, &static_generics);
 
-    let mocked_impl_item = quote_item!(cx,
-        impl ::mockers::Mocked for &'static $trait_path {
-            type MockImpl = $mock_ident;
+    let handle_ident = cx.ident_of(&format!("{}Handle", mock_ident));
+    let handle_ty = cx.ty_path(cx.path_all(sp, false, vec![handle_ident], vec![], params_tys, vec![]));
+    let handle_struct_item = quote_item!(cx,
+        pub struct $handle_ident {
+            scenario: ::std::rc::Rc<::std::cell::RefCell<::mockers::ScenarioInternals>>,
+            mock_id: usize,
+            _phantom: ::std::marker::PhantomData<$phantom_ty>,
+        }
+    ).unwrap();
+    let handle_struct_item = set_item_generics(handle_struct_item, generics).map(|mut it| {
+        it.vis = vis.unwrap_or(Visibility::Public);
+        it
+    });
+    let handle_clone_item = quote_item!(cx,
+        impl Clone for $handle_ty {
+            fn clone(&self) -> Self {
+                $handle_ident {
+                    scenario: self.scenario.clone(),
+                    mock_id: self.mock_id,
+                    _phantom: ::std::marker::PhantomData,
+                }
+            }
+        }
+    ).unwrap();
+    let handle_new_item = quote_item!(cx,
+        impl $handle_ty {
+            #[allow(dead_code)]
+            pub fn new(mock: &$mock_ty) -> Self {
+                $handle_ident {
+                    scenario: mock.scenario.clone(),
+                    mock_id: mock.mock_id,
+                    _phantom: ::std::marker::PhantomData,
+                }
+            }
         }
     ).unwrap();
 
-    if local {
-        vec![struct_item, mock_impl_item, impl_item,
-             trait_impl_item, mocked_impl_item]
-    } else {
-        vec![struct_item, mock_impl_item, impl_item,
-             trait_impl_item]
+    vec![struct_item,
+         mock_impl_item,
+         handle_struct_item,
+         set_item_generics(handle_clone_item, &static_generics),
+         set_item_generics(handle_new_item, &static_generics)]
+}
+
+/// Generate implementation of marker supertrait or common trait for mock struct.
+///
+/// Mocks are identified by mock ID, so copies made with `Clone` share
+/// expectations and are equal to each other. Mock may be printed by
+/// action while scenario is borrowed, then it is printed as `#<mock ID>`.
+fn generate_marker_impl(cx: &mut ExtCtxt, mock_ident: Ident, mock_ty: &P<Ty>, name: &str) -> Option<P<Item>> {
+    match name {
+        "Debug" => quote_item!(cx,
+            impl ::std::fmt::Debug for $mock_ty {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                    match self.scenario.try_borrow() {
+                        Ok(scenario) => f.write_str(scenario.get_mock_name(self.mock_id)),
+                        Err(_) => write!(f, "#{}", self.mock_id),
+                    }
+                }
+            }
+        ),
+        "Clone" => quote_item!(cx,
+            impl Clone for $mock_ty {
+                fn clone(&self) -> Self {
+                    $mock_ident {
+                        scenario: self.scenario.clone(),
+                        mock_id: self.mock_id,
+                        _phantom: ::std::marker::PhantomData,
+                    }
+                }
+            }
+        ),
+        "PartialEq" => quote_item!(cx,
+            impl PartialEq for $mock_ty {
+                fn eq(&self, other: &Self) -> bool {
+                    self.mock_id == other.mock_id &&
+                        &*self.scenario as *const _ == &*other.scenario as *const _
+                }
+            }
+        ),
+        "Eq" => quote_item!(cx, impl Eq for $mock_ty {}),
+        "Hash" => quote_item!(cx,
+            impl ::std::hash::Hash for $mock_ty {
+                fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+                    ::std::hash::Hash::hash(&self.mock_id, state)
+                }
+            }
+        ),
+        // `Sized` is implemented already.
+        _ => None,
     }
 }
 
-fn generate_trait_methods(cx: &mut ExtCtxt, sp: Span,
-                          method_ident: Ident, decl: &FnDecl) -> Option<GeneratedMethods> {
+fn generate_trait_methods(cx: &mut ExtCtxt, sp: Span, mock_ident: Ident, mock_key: &str,
+                          trait_generics: &Generics,
+                          method_ident: Ident, sig: &MethodSig,
+                          args_vis: Visibility) -> Option<GeneratedMethods> {
+    let decl = &sig.decl;
     match decl.get_self() {
         Some(Spanned { node: SelfKind::Value(..), ..}) |
         Some(Spanned { node: SelfKind::Region(..), ..}) => {},
 
+        // Mock fields are accessed through `Deref` of smart pointer.
+        Some(Spanned { node: SelfKind::Explicit(ref ty, _), ..}) if is_smart_pointer_to_self(ty) => {},
+
         Some(Spanned { span: sp_arg, node: SelfKind::Explicit(..)}) => {
-            cx.span_err(sp_arg, "methods with explicit `self` are not supported");
+            cx.span_err(sp_arg, "only `Box<Self>`, `Rc<Self>` and `Arc<Self>` explicit `self` types are supported");
             return None;
         },
 
-        None => {
-            cx.span_err(sp, "only non-static methods (with `self`, `&self` or `&mut self` argument) are supported");
-            return None;
-        }
+        // Static method.
+        None => {},
     };
 
-    // Arguments without `&self`.
-    let self_arg = &decl.inputs[0];
-    let args = &decl.inputs[1..];
+    // Arguments without `&self`. `extern "C"` callbacks get mock through
+    // `user_data` pointer instead of `self`, so it isn't matched too.
+    let (self_arg, args) = if decl.get_self().is_some() || is_callback(sig) {
+        (Some(&decl.inputs[0]), &decl.inputs[1..])
+    } else {
+        (None, &decl.inputs[..])
+    };
 
     let return_type = match decl.output {
         FunctionRetTy::Default(span) => cx.ty(span, TyKind::Tup(vec![])),
         FunctionRetTy::Ty(ref ty) => ty.clone(),
     };
 
-    let mock_type_id = unsafe {
-        let id = NEXT_MOCK_TYPE_ID;
-        NEXT_MOCK_TYPE_ID += 1;
-        id
-    };
+    let mock_type_id = mock_type_id(cx, sp, mock_key, method_ident, &decl.inputs, &return_type);
 
-    let trait_impl_method = generate_trait_impl_method(
-            cx, sp, mock_type_id, method_ident,
-            self_arg, args, &return_type);
-    let impl_method = generate_impl_method(cx, sp, mock_type_id, method_ident, args, &return_type);
+    let mock_id_source = if is_callback(sig) {
+        MockIdSource::UserData(sig.abi)
+    } else if self_arg.is_some() {
+        MockIdSource::Mock
+    } else {
+        MockIdSource::Static(quote_expr!(cx, &<Self as ::mockers::Mock>::mocked_type_name()))
+    };
+    // Callback forwards call to inherent method, which gets mock as `&self`
+    // like usual method.
+    let (trait_impl_method, forward_method) = match mock_id_source {
+        MockIdSource::UserData(abi) => {
+            let forward_ident = cx.ident_of(&format!("{}_callback", method_ident.name.as_str()));
+            let self_ref_arg = Arg::from_self(respan(sp, SelfKind::Region(None, Mutability::Immutable)),
+                                              respan(sp, keywords::SelfValue.ident()));
+            let forward_method = generate_trait_impl_method(
+                    cx, sp, mock_type_id.clone(), trait_generics, method_ident, &sig.generics,
+                    &MockIdSource::Mock, Some(&self_ref_arg), args, &return_type)
+                .map(|mut method| { method.ident = forward_ident; method });
+            let callback = forward_method.as_ref().and(self_arg).map(|user_data_arg| {
+                generate_callback_impl_method(cx, sp, method_ident, &sig.generics, abi, forward_ident,
+                                              user_data_arg, args, &return_type)
+            });
+            (callback, forward_method)
+        },
+        _ => (generate_trait_impl_method(
+                  cx, sp, mock_type_id.clone(), trait_generics, method_ident, &sig.generics,
+                  &mock_id_source, self_arg, args, &return_type),
+              None),
+    };
+    let impl_method = generate_impl_method(cx, sp, mock_type_id, trait_generics,
+                                           method_ident, &sig.generics,
+                                           &mock_id_source, self_arg.is_some(), args, &return_type);
 
     if let (Some(tim), Some(im)) = (trait_impl_method, impl_method) {
+        let em = generate_expect_method(cx, sp, &im, method_ident, &sig.generics,
+                                        self_arg.is_some(), args, &return_type);
+        // Arguments struct can't refer to type parameters of trait or method.
+        let call_with_items = if !args.is_empty() && trait_generics.ty_params.is_empty() &&
+                                 sig.generics.ty_params.is_empty() {
+            generate_call_with_items(cx, sp, mock_ident, &im, method_ident, self_arg.is_some(), args,
+                                     args_vis)
+        } else {
+            vec![]
+        };
         Some(GeneratedMethods {
             trait_impl_method: tim,
             impl_method: im,
+            expect_method: em,
+            forward_method: forward_method,
+            call_with_items: call_with_items,
         })
     } else {
         None
@@ -328,22 +1588,52 @@ fn generate_trait_methods(cx: &mut ExtCtxt, sp: Span,
 /// pub fn bar_call<Arg0Match: ::mockers::MatchArg<u32>>(&self,
 ///                                                      arg0: Arg0Match)
 ///  -> ::mockers::CallMatch1<u32, ()> {
-///     ::mockers::CallMatch1::new(self.mock_id, 1usize /* mock_id */,
+///     ::mockers::CallMatch1::new(self.mock_id, 1usize /* mock_id */, "bar",
+///                                ::mockers::TypeParams::of::<()>(),
+///                                { const ARG_NAMES: &'static [&'static str] = &["a"]; ARG_NAMES },
 ///                                Box::new(arg0))
 /// }
 /// ```
-fn generate_impl_method(cx: &mut ExtCtxt, sp: Span, mock_type_id: usize,
-                        method_ident: Ident, args: &[Arg],
-                        return_type: &Ty) -> Option<ImplItem> {
+///
+/// Type parameters of generic method are added before argument matcher
+/// types and get additional `'static` bound, so for `fn get<T>(&self)`
+/// `get_call<T: 'static>(&self)` is generated, which may be called
+/// like `get_call::<u32>()`.
+///
+/// Matchers for `&T` and `&mut T` arguments match referenced value,
+/// they must implement `MatchArg<T>`. `ByRef` and `ByMutRef` markers are
+/// added to call matcher type then, so actions and predicates get such
+/// arguments for any lifetime, like `for<'a> FnOnce(&'a T)`, and can't
+/// keep them after call. Lifetime parameters of method are dropped.
+///
+/// For static trait method static `*_call` method is generated too,
+/// it is used like `FooMock::new_call(ANY)`.
+fn generate_impl_method(cx: &mut ExtCtxt, sp: Span, mock_type_id: P<Expr>,
+                        trait_generics: &Generics,
+                        method_ident: Ident, method_generics: &Generics,
+                        mock_id_source: &MockIdSource, has_self: bool,
+                        args: &[Arg], return_type: &Ty) -> Option<ImplItem> {
+    if !check_arg_lifetimes(cx, args) {
+        return None;
+    }
+
+    // Method type parameters must be `'static` to be stored in expectation.
+    let mut arg_matcher_types: Vec<TyParam> =
+        add_static_bounds(cx, sp, method_generics).ty_params.to_vec();
+    let type_params: Vec<Ident> = trait_generics.ty_params.iter()
+        .chain(method_generics.ty_params.iter())
+        .map(|p| p.ident).collect();
+
     // For each argument generate...
-    let mut arg_matcher_types = Vec::<TyParam>::new();
     let mut inputs = Vec::<Arg>::new();
     let mut new_args = Vec::<P<Expr>>::new();
-    new_args.push(cx.expr_field_access(sp, cx.expr_self(sp), cx.ident_of("mock_id")));
-    new_args.push(quote_expr!(cx, $mock_type_id));
+    new_args.push(mock_id_source.mock_id_expr(cx, sp));
+    new_args.push(mock_type_id);
     new_args.push(cx.expr_str(sp, method_ident.name));
+    new_args.push(type_params_expr(cx, sp, &type_params));
+    new_args.push(arg_names_expr(cx, sp, args));
     for (i, arg) in args.iter().enumerate() {
-        let arg_type = &arg.ty;
+        let (arg_type, _) = arg_kind(&arg.ty);
         let arg_type_ident = cx.ident_of(&format!("Arg{}Match", i));
         let arg_ident = cx.ident_of(&format!("arg{}", i));
 
@@ -369,8 +1659,7 @@ fn generate_impl_method(cx: &mut ExtCtxt, sp: Span, mock_type_id: usize,
 
     let call_match_ident = cx.ident_of(&format!("CallMatch{}", args.len()));
 
-    let mut call_match_args: Vec<_> = args.iter().map(|arg| arg.ty.clone()).collect();
-    call_match_args.push(P(return_type.clone()));
+    let call_match_args = call_match_type_args(cx, sp, args, return_type);
     let ret_type = cx.path_all(
         sp,
         true,
@@ -385,10 +1674,8 @@ fn generate_impl_method(cx: &mut ExtCtxt, sp: Span, mock_type_id: usize,
         span: sp,
         lifetimes: vec![],
         ty_params: p_vec(arg_matcher_types),
-        where_clause: WhereClause {
-            id: DUMMY_NODE_ID,
-            predicates: vec![],
-        }
+        where_clause: fold::noop_fold_where_clause(method_generics.where_clause.clone(),
+                                                   &mut StaticLifetimesFolder { sp: sp }),
     };
 
     // nightly: let new_method_path = quote_path!(cx, ::mockers::$call_match_ident::new);
@@ -397,9 +1684,11 @@ fn generate_impl_method(cx: &mut ExtCtxt, sp: Span, mock_type_id: usize,
     let body = cx.block_expr(body_expr);
     let mut ainputs = inputs.clone();
 
-    let self_arg = Arg::from_self(respan(sp, SelfKind::Region(None, Mutability::Immutable)),
-                                  respan(sp, keywords::SelfValue.ident()));
-    ainputs.insert(0, self_arg.clone());
+    if has_self {
+        let self_arg = Arg::from_self(respan(sp, SelfKind::Region(None, Mutability::Immutable)),
+                                      respan(sp, keywords::SelfValue.ident()));
+        ainputs.insert(0, self_arg.clone());
+    }
 
     let call_sig = MethodSig {
         unsafety: Unsafety::Normal,
@@ -427,6 +1716,221 @@ fn generate_impl_method(cx: &mut ExtCtxt, sp: Span, mock_type_id: usize,
     Some(impl_subitem)
 }
 
+/// Generate mock method adding expectation to scenario and returning
+/// builder for it, expectation matches any arguments by default.
+///
+/// Example of method generated for trait method `fn bar(&self, a: u32)`:
+/// ```
+/// #[allow(dead_code)]
+/// pub fn expect_bar(&self) -> ::mockers::ExpectationBuilder1<u32, ()> {
+///     ::mockers::ExpectationBuilder1::new(self.scenario.clone(),
+///                                         self.bar_call(::mockers::matchers::ANY))
+///         .returning(|_arg0| {})
+/// }
+/// ```
+/// Result needn't be given for methods returning `()`, so default
+/// action is set for them.
+fn generate_expect_method(cx: &mut ExtCtxt, sp: Span, call_method: &ImplItem,
+                          method_ident: Ident, method_generics: &Generics,
+                          has_self: bool, args: &[Arg], return_type: &Ty) -> ImplItem {
+    let builder_ident = cx.ident_of(&format!("ExpectationBuilder{}", args.len()));
+    let builder_args = call_match_type_args(cx, sp, args, return_type);
+    let output = cx.ty_path(cx.path_all(sp, true, vec![cx.ident_of("mockers"), builder_ident],
+                                        vec![], builder_args, vec![]));
+
+    // Method type parameters are inferred from `*_call` result type.
+    let where_clause = match call_method.node {
+        ImplItemKind::Method(ref call_sig, _) => call_sig.generics.where_clause.clone(),
+        _ => unreachable!(),
+    };
+    let generics = Generics {
+        span: sp,
+        lifetimes: vec![],
+        ty_params: add_static_bounds(cx, sp, method_generics).ty_params,
+        where_clause: where_clause,
+    };
+
+    let call_ident = call_method.ident;
+    let any_args: Vec<P<Expr>> = args.iter().map(|_| quote_expr!(cx, ::mockers::matchers::ANY)).collect();
+    let (scenario_expr, call_expr) = if has_self {
+        (quote_expr!(cx, self.scenario.clone()),
+         cx.expr_method_call(sp, cx.expr_self(sp), call_ident, any_args))
+    } else {
+        let call_path = cx.path(sp, vec![keywords::SelfType.ident(), call_ident]);
+        (quote_expr!(cx, ::mockers::current_scenario()),
+         cx.expr_call(sp, cx.expr_path(call_path), any_args))
+    };
+    let new_path = cx.path_global(sp, vec![cx.ident_of("mockers"), builder_ident, cx.ident_of("new")]);
+    let mut body_expr = cx.expr_call(sp, cx.expr_path(new_path), vec![scenario_expr, call_expr]);
+    let returns_unit = match return_type.node {
+        TyKind::Tup(ref tys) => tys.is_empty(),
+        _ => false,
+    };
+    if returns_unit {
+        let params = (0..args.len()).map(|i| cx.ident_of(&format!("_arg{}", i))).collect();
+        // Closure built by `ExtCtxt` has `-> _` return type, which syntex
+        // prints as is, and it is only valid with block body.
+        let action = cx.lambda_stmts(sp, params, vec![]);
+        body_expr = cx.expr_method_call(sp, body_expr, cx.ident_of("returning"), vec![action]);
+    }
+
+    let mut inputs = Vec::new();
+    if has_self {
+        inputs.push(Arg::from_self(respan(sp, SelfKind::Region(None, Mutability::Immutable)),
+                                   respan(sp, keywords::SelfValue.ident())));
+    }
+    let sig = MethodSig {
+        unsafety: Unsafety::Normal,
+        constness: respan(sp, Constness::NotConst),
+        abi: Abi::Rust,
+        decl: P(FnDecl {
+            inputs: inputs,
+            output: FunctionRetTy::Ty(output),
+            variadic: false,
+        }),
+        generics: generics,
+    };
+
+    ImplItem {
+        id: DUMMY_NODE_ID,
+        ident: cx.ident_of(&format!("expect_{}", method_ident.name.as_str())),
+        vis: Visibility::Public,
+        attrs: vec![cx.attribute(sp, cx.meta_list(sp, Symbol::intern("allow"), vec![cx.meta_list_item_word(sp, Symbol::intern("dead_code"))]))],
+        node: ImplItemKind::Method(sig, cx.block_expr(body_expr)),
+        span: sp,
+        defaultness: Defaultness::Final,
+    }
+}
+
+/// Generate struct with all arguments of method and mock method
+/// creating call matcher with predicate checking them together.
+///
+/// Example of items generated for method `fn set(&self, key: i16, value: i32)`
+/// of mock `StoreMock`:
+/// ```
+/// #[allow(dead_code)]
+/// pub struct StoreMockSetArgs<'a> {
+///     pub key: &'a i16,
+///     pub value: &'a i32,
+/// }
+/// impl StoreMock {
+///     #[allow(dead_code)]
+///     pub fn set_call_with<F: Fn(&StoreMockSetArgs) -> bool + 'static>(&self, predicate: F)
+///             -> ::mockers::CallMatch2<i16, i32, ()> {
+///         self.set_call(::mockers::matchers::ANY, ::mockers::matchers::ANY)
+///             .with_predicate(move |args| {
+///                 predicate(&StoreMockSetArgs { key: &args.0, value: &args.1 })
+///             })
+///     }
+/// }
+/// ```
+/// Fields for arguments declared with patterns are named `arg0`, `arg1`, ….
+/// Fields for `&T` and `&mut T` arguments have `&'a T` type. Struct, its
+/// fields and `*_call_with` method have given visibility instead of `pub`.
+/// Non-static `*_call_with` method is added to `StoreMockHandle` too.
+fn generate_call_with_items(cx: &mut ExtCtxt, sp: Span, mock_ident: Ident, call_method: &ImplItem,
+                            method_ident: Ident, has_self: bool, args: &[Arg],
+                            vis: Visibility) -> Vec<P<Item>> {
+    let camel_name: String = method_ident.name.as_str().split('_').map(|part| {
+        let mut chars = part.chars();
+        chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or(String::new())
+    }).collect();
+    let args_ident = cx.ident_of(&format!("{}{}Args", mock_ident, camel_name));
+    let field_idents: Vec<Ident> = arg_names(args).into_iter().enumerate().map(|(i, name)| {
+        name.unwrap_or_else(|| cx.ident_of(&format!("arg{}", i)))
+    }).collect();
+    let kinds: Vec<(P<Ty>, &'static str)> = args.iter().map(|arg| arg_kind(&arg.ty)).collect();
+
+    let lifetime = cx.lifetime(sp, Symbol::intern("'a"));
+    let fields = field_idents.iter().zip(kinds.iter()).map(|(&ident, &(ref ty, _))| StructField {
+        span: sp,
+        ident: Some(ident),
+        vis: vis.clone(),
+        id: DUMMY_NODE_ID,
+        ty: cx.ty_rptr(sp, ty.clone(), Some(lifetime), Mutability::Immutable),
+        attrs: vec![],
+    }).collect();
+    let struct_generics = Generics {
+        span: sp,
+        lifetimes: vec![cx.lifetime_def(sp, Symbol::intern("'a"), vec![], vec![])],
+        ..Generics::default()
+    };
+    let struct_item = cx.item_struct_poly(sp, args_ident, VariantData::Struct(fields, DUMMY_NODE_ID),
+                                          struct_generics).map(|mut it| {
+        it.vis = vis.clone();
+        it.attrs.push(cx.attribute(sp, cx.meta_list(sp, Symbol::intern("allow"),
+                                                    vec![cx.meta_list_item_word(sp, Symbol::intern("dead_code"))])));
+        it
+    });
+
+    let call_match_ty = match call_method.node {
+        ImplItemKind::Method(ref sig, _) => match sig.decl.output {
+            FunctionRetTy::Ty(ref ty) => ty.clone(),
+            FunctionRetTy::Default(_) => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+    let args_fields = field_idents.iter().zip(kinds.iter()).enumerate().map(|(i, (&ident, &(_, kind)))| {
+        let mut arg = cx.expr_tup_field_access(sp, quote_expr!(cx, args), i);
+        if kind != "ByValue" {
+            arg = cx.expr_deref(sp, arg);
+        }
+        cx.field_imm(sp, ident, cx.expr_addr_of(sp, arg))
+    }).collect();
+    let args_expr = cx.expr_struct_ident(sp, args_ident, args_fields);
+    let any_args: Vec<P<Expr>> = args.iter().map(|_| quote_expr!(cx, ::mockers::matchers::ANY)).collect();
+    let any_args_sep = comma_sep(&any_args);
+    let call_ident = call_method.ident;
+    let call_with_ident = cx.ident_of(&format!("{}_call_with", method_ident.name.as_str()));
+    let predicate_expr = quote_expr!(cx, move |args| predicate(&$args_expr));
+
+    let impl_item = if has_self {
+        quote_item!(cx,
+            impl $mock_ident {
+                #[allow(dead_code)]
+                pub fn $call_with_ident<F: Fn(&$args_ident) -> bool + 'static>(&self, predicate: F)
+                        -> $call_match_ty {
+                    self.$call_ident($any_args_sep).with_predicate($predicate_expr)
+                }
+            }
+        )
+    } else {
+        quote_item!(cx,
+            impl $mock_ident {
+                #[allow(dead_code)]
+                pub fn $call_with_ident<F: Fn(&$args_ident) -> bool + 'static>(predicate: F)
+                        -> $call_match_ty {
+                    Self::$call_ident($any_args_sep).with_predicate($predicate_expr)
+                }
+            }
+        )
+    }.unwrap().map(|mut it| {
+        if let ItemKind::Impl(_, _, _, _, _, ref mut items) = it.node {
+            for item in items.iter_mut() {
+                item.vis = vis.clone();
+            }
+        }
+        it
+    });
+
+    // Handle has the same `*_call` methods, so it gets `*_call_with` too.
+    let handle_impl_item = if has_self {
+        let handle_ty = cx.ty_ident(sp, cx.ident_of(&format!("{}Handle", mock_ident)));
+        Some(impl_item.clone().map(|mut it| {
+            if let ItemKind::Impl(_, _, _, _, ref mut self_ty, _) = it.node {
+                *self_ty = handle_ty;
+            }
+            it
+        }))
+    } else {
+        None
+    };
+
+    let mut items = vec![struct_item, impl_item];
+    items.extend(handle_impl_item);
+    items
+}
+
 /// Generate mocked trait method implementation for mock struct.
 ///
 /// Implementation just packs all arguments into tuple and
@@ -439,8 +1943,8 @@ fn generate_impl_method(cx: &mut ExtCtxt, sp: Span, mock_type_id: usize,
 ///
 /// following implementation will be generated:
 /// ```
-/// fn method(&self, foo: i32, bar: u16) -> u8 {
-///     let args = (foo, bar);
+/// fn method(&self, arg0: i32, arg1: u16) -> u8 {
+///     let args = (arg0, arg1);
 ///     let args_ptr: *const u8 = unsafe { std::mem::transmute(&args) };
 ///     let result_ptr: *mut u8 =
 ///         self.scenario.borrow_mut().verify(self.mock_id, 0 /* mock_id */, args_ptr);
@@ -449,71 +1953,105 @@ fn generate_impl_method(cx: &mut ExtCtxt, sp: Span, mock_type_id: usize,
 /// }
 /// ```
 /// where constant marked with `mock_id` is unique trait method ID.
-fn generate_trait_impl_method(cx: &mut ExtCtxt, sp: Span, mock_type_id: usize,
-                              method_ident: Ident, self_arg: &Arg,
-                              args: &[Arg], return_type: &Ty) -> Option<ImplItem> {
+///
+/// Nested `destroy` and `format_args` functions can't use type parameters
+/// of generic trait or method, so they get their own ones and are instantiated
+/// explicitly. Arguments which types depend on type parameters aren't
+/// required to implement `Debug`, so they are formatted as `_`.
+///
+/// Static methods have no `self` to take scenario from, so current
+/// scenario of the thread is used and calls are attributed to
+/// pseudo-mock named after mocked class.
+fn generate_trait_impl_method(cx: &mut ExtCtxt, sp: Span, mock_type_id: P<Expr>,
+                              trait_generics: &Generics,
+                              method_ident: Ident, method_generics: &Generics,
+                              mock_id_source: &MockIdSource,
+                              self_arg: Option<&Arg>, args: &[Arg],
+                              return_type: &Ty) -> Option<ImplItem> {
     let method_name = cx.expr_str(sp, method_ident.name);
+    let all_params: Vec<TyParam> = trait_generics.ty_params.iter()
+        .chain(method_generics.ty_params.iter())
+        .cloned().collect();
+    let type_params: Vec<Ident> = all_params.iter().map(|p| p.ident).collect();
+    let type_params_tys: Vec<P<Ty>> = type_params.iter().map(|&p| cx.ty_ident(sp, p)).collect();
+    let type_params_sep = comma_sep(&type_params);
+    let destroy_path = cx.expr_path(cx.path_all(sp, false, vec![cx.ident_of("destroy")],
+                                                vec![], type_params_tys.clone(), vec![]));
+    let format_args_path = cx.expr_path(cx.path_all(sp, false, vec![cx.ident_of("format_args")],
+                                                    vec![], type_params_tys, vec![]));
+    let type_params = type_params_expr(cx, sp, &type_params);
+    let arg_names = arg_names_expr(cx, sp, args);
+    // Arguments may be declared with any patterns, like `_` or `(a, b)`,
+    // so they are bound to `arg0`, `arg1`, ... instead.
+    let arg_idents: Vec<Ident> = (0..args.len()).map(|i| cx.ident_of(&format!("arg{}", i))).collect();
     // Generate expression returning tuple of all method arguments.
-    let tuple_values: Vec<P<Expr>> =
-        args.iter().flat_map(|i| {
-            if let PatKind::Ident(_, SpannedIdent {node: ident, ..}, _) = i.pat.node {
-                Some(cx.expr_ident(sp, ident))
-            } else {
-                cx.span_err(i.pat.span, "Only identifiers are accepted in argument list");
-                return None;
-            }
-        }).collect();
-    if tuple_values.len() < args.len() { return None }
+    let tuple_values: Vec<P<Expr>> = arg_idents.iter().map(|&ident| cx.expr_ident(sp, ident)).collect();
     let args_tuple = cx.expr_tuple(sp, tuple_values);
 
-    let args_type: Vec<P<Ty>> = args.iter().map(|a| a.ty.clone()).collect();
+    // Nested functions can't refer to lifetime parameters of method.
+    let args_type: Vec<P<Ty>> = args.iter().map(|a| erase_lifetimes(sp, &a.ty)).collect();
     let args_tuple_type: P<Ty> = cx.ty(sp, TyKind::Tup(args_type));
 
-    let mut call_match_args: Vec<_> = args.iter().map(|arg| arg.ty.clone()).collect();
-    call_match_args.push(P(return_type.clone()));
-
-    let args_format_str = std::iter::repeat("{:?}").take(args.len()).join(", ");
-    let args_tuple_fields: Vec<_> = (0..args.len()).map(|i| {
+    let generic_args: Vec<bool> = args.iter().map(|a| {
+        ty_mentions_params(&a.ty, &all_params)
+    }).collect();
+    let args_format_str = generic_args.iter().map(|&generic| {
+        if generic { "_" } else { "{:?}" }
+    }).join(", ");
+    let args_tuple_fields: Vec<_> = (0..args.len()).filter(|&i| !generic_args[i]).map(|i| {
         cx.expr_tup_field_access(sp, quote_expr!(cx, _args_ref), i)
     }).collect();
     let args_tuple_fields_sep = comma_sep(&args_tuple_fields);
-
-    let self_ident = if let PatKind::Ident(_, spanned_ident, _) = self_arg.pat.node {
-        spanned_ident.node
+    // `format!` without arguments is useless, format string is used as is.
+    let args_format_expr = if args_tuple_fields.is_empty() {
+        quote_expr!(cx, $args_format_str.to_owned())
     } else {
-        cx.span_err(sp, "Patterns for `self` argument are not supported");
-        return None;
+        quote_expr!(cx, format!($args_format_str, $args_tuple_fields_sep))
+    };
+
+    let (mock_id_expr, scenario_expr) = match (mock_id_source, self_arg) {
+        (&MockIdSource::Mock, Some(self_arg)) => {
+            let self_ident = if let PatKind::Ident(_, spanned_ident, _) = self_arg.pat.node {
+                spanned_ident.node
+            } else {
+                cx.span_err(sp, "Patterns for `self` argument are not supported");
+                return None;
+            };
+            (quote_expr!(cx, $self_ident.mock_id), quote_expr!(cx, $self_ident.scenario))
+        },
+        _ => (mock_id_source.mock_id_expr(cx, sp), quote_expr!(cx, ::mockers::current_scenario())),
     };
 
     let fn_mock = quote_block!(cx, {
         let args = Box::new($args_tuple);
         let args_ptr: *const u8 = ::std::boxed::Box::into_raw(args) as *const u8;
-        fn destroy(args_to_destroy: *const u8) {
+        fn destroy<$type_params_sep>(args_to_destroy: *const u8) {
             unsafe { Box::from_raw(args_to_destroy as *mut $args_tuple_type) };
         }
-        fn format_args(args_ptr: *const u8) -> String {
-            let _args_ref: &$args_tuple_type = unsafe { ::std::mem::transmute(args_ptr) };
-            format!($args_format_str, $args_tuple_fields_sep)
+        fn format_args<$type_params_sep>(args_ptr: *const u8) -> String {
+            let _args_ref: &$args_tuple_type = unsafe { &*(args_ptr as *const $args_tuple_type) };
+            $args_format_expr
         }
-        let call = ::mockers::Call { mock_id: $self_ident.mock_id,
+        let call = ::mockers::Call { mock_id: $mock_id_expr,
                                      mock_type_id: $mock_type_id,
                                      method_name: $method_name,
+                                     type_params: $type_params,
+                                     arg_names: $arg_names,
                                      args_ptr: args_ptr,
-                                     destroy: destroy,
-                                     format_args: format_args };
-        let result_ptr: *mut u8 = $self_ident.scenario.borrow_mut().verify(call);
+                                     destroy: $destroy_path,
+                                     format_args: $format_args_path };
+        // Scenario is bound to variable, so that it outlives `RefMut` borrowing it.
+        let scenario = &$scenario_expr;
+        let result_ptr: *mut u8 = scenario.borrow_mut().verify(call);
         let result: Box<$return_type> = unsafe { Box::from_raw(result_ptr as *mut $return_type) };
         *result
     }).unwrap();
-
-    let mut impl_args: Vec<Arg> = args.iter().map(|a| {
-        let ident = match a.pat.node {
-            PatKind::Ident(_, ident, _) => ident,
-            _ => panic!("argument pattern"),
-        };
-        cx.arg(sp, ident.node, a.ty.clone())
+    let mut impl_args: Vec<Arg> = args.iter().zip(arg_idents.iter()).map(|(a, &ident)| {
+        cx.arg(sp, ident, a.ty.clone())
     }).collect();
-    impl_args.insert(0, self_arg.clone());
+    if let Some(self_arg) = self_arg {
+        impl_args.insert(0, self_arg.clone());
+    }
     let impl_sig = MethodSig {
         unsafety: Unsafety::Normal,
         constness: respan(sp, Constness::NotConst),
@@ -523,14 +2061,23 @@ fn generate_trait_impl_method(cx: &mut ExtCtxt, sp: Span, mock_type_id: usize,
             output: FunctionRetTy::Ty(P(return_type.clone())),
             variadic: false,
         }),
-        generics: Generics::default(),
+        generics: method_generics.clone(),
     };
+    // nightly: let mut attrs = vec![quote_attr!(cx, #[allow(unused_mut)])];
+    let mut attrs = vec![cx.attribute(sp, cx.meta_list(sp, Symbol::intern("allow"), vec![cx.meta_list_item_word(sp, Symbol::intern("unused_mut"))]))];
+    // Clippy suggests to take `self` unboxed, but receiver type is
+    // dictated by trait.
+    let explicit_self = self_arg.and_then(|arg| arg.to_self()).map_or(false, |explicit_self| {
+        match explicit_self.node { SelfKind::Explicit(..) => true, _ => false }
+    });
+    if explicit_self {
+        attrs.push(clippy_allow_attr(cx, sp, "boxed_local"));
+    }
     let trait_impl_subitem = ImplItem {
         id: DUMMY_NODE_ID,
         ident: method_ident,
         vis: Visibility::Inherited,
-        // nightly: attrs: vec![quote_attr!(cx, #[allow(unused_mut)])],
-        attrs: vec![cx.attribute(sp, cx.meta_list(sp, Symbol::intern("allow"), vec![cx.meta_list_item_word(sp, Symbol::intern("unused_mut"))]))],
+        attrs: attrs,
         node: ImplItemKind::Method(impl_sig, nightly_p(fn_mock)),
         span: sp,
         defaultness: Defaultness::Final,
@@ -539,6 +2086,230 @@ fn generate_trait_impl_method(cx: &mut ExtCtxt, sp: Span, mock_type_id: usize,
     Some(trait_impl_subitem)
 }
 
+/// Generate `extern "C"` callback method for trait implementation.
+///
+/// Callbacks have no `self`, but they get pointer to mock as `user_data`
+/// argument, which isn't passed to scenario. Call is forwarded to
+/// inherent method of mock, generated like usual trait impl method.
+/// It is run under `catch_unwind` and panic aborts process.
+fn generate_callback_impl_method(cx: &mut ExtCtxt, sp: Span,
+                                 method_ident: Ident, method_generics: &Generics,
+                                 abi: Abi, forward_ident: Ident,
+                                 user_data_arg: &Arg, args: &[Arg],
+                                 return_type: &Ty) -> ImplItem {
+    let method_name = cx.expr_str(sp, method_ident.name);
+    let arg_idents: Vec<Ident> = (0..args.len()).map(|i| cx.ident_of(&format!("arg{}", i))).collect();
+    let arg_values: Vec<P<Expr>> = arg_idents.iter().map(|&ident| cx.expr_ident(sp, ident)).collect();
+    let forward_call = cx.expr_method_call(sp, quote_expr!(cx, user_data_mock), forward_ident, arg_values);
+
+    // Callback is safe function, so at least null pointer, which C code
+    // passes when `user_data` wasn't set, must not be dereferenced.
+    // Any other pointer must be given by `user_data()` of live mock,
+    // it can't be checked.
+    //
+    // Callback is called by C code, and unwinding out of `extern "C"`
+    // function is undefined behaviour. So panic, caused by null `user_data`
+    // or by unexpected call, aborts process. Panic message is already
+    // printed by panic hook at this point.
+    let fn_mock = quote_block!(cx, {
+        match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(move || {
+            if user_data.is_null() {
+                panic!("null `user_data` is passed to `{}` callback, pass `user_data()` of mock",
+                       $method_name);
+            }
+            let user_data_mock: &Self = unsafe { &*(user_data as *const Self) };
+            $forward_call
+        })) {
+            Ok(result) => result,
+            Err(_) => {
+                let _ = ::std::io::Write::write_fmt(
+                    &mut ::std::io::stderr(),
+                    format_args!("`{}` callback panicked, aborting, because unwinding into C code is undefined behaviour\n",
+                                 $method_name));
+                ::mockers::abort()
+            }
+        }
+    }).unwrap();
+
+    let mut impl_args: Vec<Arg> = args.iter().zip(arg_idents.iter()).map(|(a, &ident)| {
+        cx.arg(sp, ident, a.ty.clone())
+    }).collect();
+    impl_args.insert(0, cx.arg(sp, cx.ident_of("user_data"), user_data_arg.ty.clone()));
+    let impl_sig = MethodSig {
+        unsafety: Unsafety::Normal,
+        constness: respan(sp, Constness::NotConst),
+        abi: abi,
+        decl: P(FnDecl {
+            inputs: impl_args,
+            output: FunctionRetTy::Ty(P(return_type.clone())),
+            variadic: false,
+        }),
+        generics: method_generics.clone(),
+    };
+    ImplItem {
+        id: DUMMY_NODE_ID,
+        ident: method_ident,
+        vis: Visibility::Inherited,
+        attrs: vec![],
+        node: ImplItemKind::Method(impl_sig, nightly_p(fn_mock)),
+        span: sp,
+        defaultness: Defaultness::Final,
+    }
+}
+
+/// Generate `#[cfg_attr(feature = "cargo-clippy", allow(lint))]` attribute.
+fn clippy_allow_attr(cx: &ExtCtxt, sp: Span, lint: &str) -> Attribute {
+    let feature = cx.meta_name_value(sp, Symbol::intern("feature"),
+                                     LitKind::Str(Symbol::intern("cargo-clippy"), StrStyle::Cooked));
+    let allow = cx.meta_list(sp, Symbol::intern("allow"), vec![cx.meta_list_item_word(sp, Symbol::intern(lint))]);
+    cx.attribute(sp, cx.meta_list(sp, Symbol::intern("cfg_attr"), vec![
+        respan(sp, NestedMetaItemKind::MetaItem(feature)),
+        respan(sp, NestedMetaItemKind::MetaItem(allow)),
+    ]))
+}
+
+/// Whether method is `extern "C"` callback, i.e. static method which gets
+/// mock through `user_data` pointer passed as first argument.
+fn is_callback(sig: &MethodSig) -> bool {
+    (sig.abi == Abi::C || sig.abi == Abi::System) &&
+        sig.decl.get_self().is_none() &&
+        sig.decl.inputs.first().map_or(false, |arg| match arg.ty.node {
+            TyKind::Ptr(..) => true,
+            _ => false,
+        })
+}
+
+/// Where mocked method and call matcher get mock ID from.
+enum MockIdSource {
+    /// `self.mock_id` of mock object.
+    Mock,
+    /// Pseudo-mock which static methods of mocked class are attributed to,
+    /// expression gives name of class as `&str`. Current scenario of
+    /// the thread is used for them.
+    Static(P<Expr>),
+    /// Mock object which `user_data` pointer of `extern "C"` callback
+    /// with given ABI points to.
+    UserData(Abi),
+}
+impl MockIdSource {
+    fn mock_id_expr(&self, cx: &mut ExtCtxt, sp: Span) -> P<Expr> {
+        match *self {
+            MockIdSource::Mock | MockIdSource::UserData(_) =>
+                cx.expr_field_access(sp, cx.expr_self(sp), cx.ident_of("mock_id")),
+            MockIdSource::Static(ref class_name) =>
+                quote_expr!(cx, ::mockers::static_mock_id($class_name)),
+        }
+    }
+}
+
+/// Names of method arguments, arguments declared with patterns
+/// other than identifiers have no names.
+fn arg_names(args: &[Arg]) -> Vec<Option<Ident>> {
+    args.iter().map(|arg| match arg.pat.node {
+        PatKind::Ident(_, spanned_ident, _) => Some(spanned_ident.node),
+        _ => None,
+    }).collect()
+}
+
+/// Generate `&'static [&'static str]` expression with names of arguments,
+/// they are used in descriptions of calls and expectations.
+fn arg_names_expr(cx: &mut ExtCtxt, sp: Span, args: &[Arg]) -> P<Expr> {
+    let names = arg_names(args).into_iter().map(|name| {
+        cx.expr_str(sp, name.map_or(Symbol::intern(""), |ident| ident.name))
+    }).collect();
+    let names = cx.expr_vec_slice(sp, names);
+    quote_expr!(cx, { const ARG_NAMES: &'static [&'static str] = $names; ARG_NAMES })
+}
+
+/// Generate expression identifying method type parameters,
+/// `::mockers::TypeParams::of::<(T, U)>()`.
+fn type_params_expr(cx: &mut ExtCtxt, sp: Span, type_params: &[Ident]) -> P<Expr> {
+    let tys = type_params.iter().map(|&p| cx.ty_ident(sp, p)).collect();
+    let tuple_ty = cx.ty(sp, TyKind::Tup(tys));
+    quote_expr!(cx, ::mockers::TypeParams::of::<$tuple_ty>())
+}
+
+/// Copy trait generics for use in impl, defaults of type
+/// parameters aren't allowed there.
+fn impl_generics(generics: &Generics) -> Generics {
+    let mut generics = generics.clone();
+    let ty_params: Vec<TyParam> = generics.ty_params.iter().map(|param| {
+        let mut param = param.clone();
+        param.default = None;
+        param
+    }).collect();
+    generics.ty_params = p_vec(ty_params);
+    generics
+}
+
+/// Copy generics for use in impl adding `'static` bound to each type parameter.
+fn add_static_bounds(cx: &mut ExtCtxt, sp: Span, generics: &Generics) -> Generics {
+    let mut generics = impl_generics(generics);
+    let ty_params: Vec<TyParam> = generics.ty_params.iter().map(|param| {
+        let mut param = param.clone();
+        let mut bounds = param.bounds.to_vec();
+        bounds.push(TyParamBound::RegionTyParamBound(cx.lifetime(sp, cx.name_of("'static"))));
+        param.bounds = p_vec(bounds);
+        param
+    }).collect();
+    generics.ty_params = p_vec(ty_params);
+    generics
+}
+
+/// Replace generics of struct or impl item.
+///
+/// It is used for items generated with `quote_item!`, because
+/// there is no way to interpolate generics into it.
+fn set_item_generics(item: P<Item>, generics: &Generics) -> P<Item> {
+    item.map(|mut item| {
+        match item.node {
+            ItemKind::Struct(_, ref mut item_generics) |
+            ItemKind::Impl(_, _, ref mut item_generics, _, _, _) => {
+                *item_generics = generics.clone();
+            },
+            _ => unreachable!(),
+        }
+        item
+    })
+}
+
+/// Copy path replacing type parameters of the last segment.
+fn path_with_params(cx: &mut ExtCtxt, sp: Span, path: &Path, types: Vec<P<Ty>>) -> Path {
+    path_with_params_and_bindings(cx, sp, path, types, vec![])
+}
+
+/// Copy path replacing type parameters and associated type
+/// bindings of the last segment.
+fn path_with_params_and_bindings(cx: &mut ExtCtxt, sp: Span, path: &Path,
+                                 types: Vec<P<Ty>>, bindings: Vec<TypeBinding>) -> Path {
+    let idents = path.segments.iter().map(|s| s.identifier).collect();
+    cx.path_all(sp, path_is_global(path), idents, vec![], types, bindings)
+}
+
+/// Check whether explicit `self` type is `Box<Self>`, `Rc<Self>` or `Arc<Self>`,
+/// possibly given with full path like `::std::rc::Rc<Self>`.
+fn is_smart_pointer_to_self(ty: &Ty) -> bool {
+    let ty_str: String = pprust::ty_to_string(ty).chars().filter(|c| !c.is_whitespace()).collect();
+    if !ty_str.ends_with("<Self>") {
+        return false;
+    }
+    let path = &ty_str[..ty_str.len() - "<Self>".len()];
+    match path.rsplit("::").next() {
+        Some("Box") | Some("Rc") | Some("Arc") => true,
+        _ => false,
+    }
+}
+
+/// Check whether type refers to any of given type parameters.
+///
+/// It is enough to look for parameter names among type identifiers,
+/// false positives only lead to less informative formatting of arguments.
+fn ty_mentions_params(ty: &Ty, params: &[TyParam]) -> bool {
+    let ty_str = pprust::ty_to_string(ty);
+    ty_str.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+          .any(|word| params.iter().any(|p| &*p.ident.name.as_str() == word))
+}
+
 /// `quote_block!` macro in nightly and `quasi` return
 /// different types: `Block` in nightly and `P<Block>`
 /// in `quasi`, so in nightly it must be wrapped with
@@ -561,6 +2332,17 @@ fn create_path(sp: Span) -> Path {
     Path { span: sp, global: true, segments: vec![] }
 }
 
+#[cfg(not(feature="with-syntex"))]
+fn path_is_global(_path: &Path) -> bool {
+    // Global paths start with `{{root}}` segment, which is
+    // preserved by `path_with_params`.
+    false
+}
+#[cfg(feature="with-syntex")]
+fn path_is_global(path: &Path) -> bool {
+    path.global
+}
+
 #[cfg(feature="with-syntex")]
 fn create_path_segment(ident: Ident) -> PathSegment {
     PathSegment {
@@ -1,4 +1,7 @@
 #![cfg_attr(not(feature="with-syntex"), feature(quote, rustc_private))]
+// Code generation functions get all parts of mocked method. Lint is allowed
+// for whole crate, because syntex strips `cfg_attr` from lib.in.rs.
+#![cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
 
 #[cfg(feature="with-syntex")] extern crate quasi;
 #[cfg(feature="with-syntex")] extern crate syntex_syntax as syntax;
@@ -13,6 +16,11 @@ extern crate rustc_plugin;
 #[cfg(feature = "with-syntex")]
 extern crate syntex;
 
+/// Implementation of `mockers-gen` tool.
+#[cfg(feature = "with-syntex")]
+#[doc(hidden)]
+pub mod cli;
+
 #[cfg(feature = "with-syntex")]
 fn syntex_registry() -> syntex::Registry {
     let mut reg = syntex::Registry::new();
@@ -21,21 +29,54 @@ fn syntex_registry() -> syntex::Registry {
     reg.add_attr("feature(custom_attribute)");
 
     reg.add_macro("mock", generate_mock);
+    reg.add_macro("mock_from_file", generate_mock_from_file);
     reg.add_decorator("derive_Mock", derive_mock);
+    reg.add_modifier("mockable", mockable);
+
+    reg.add_post_expansion_pass(strip_attributes);
 
     reg
 }
 
+/// Remove `#[mock(...)]` attributes after expansion, because they
+/// are unknown to stable compiler.
+#[cfg(feature = "with-syntex")]
+fn strip_attributes(krate: syntax::ast::Crate) -> syntax::ast::Crate {
+    use syntax::ast::Attribute;
+    use syntax::fold::{self, Folder};
+
+    struct StripAttributeFolder;
+    impl Folder for StripAttributeFolder {
+        fn fold_attribute(&mut self, attr: Attribute) -> Option<Attribute> {
+            if attr.check_name("mock") {
+                None
+            } else {
+                Some(attr)
+            }
+        }
+
+        fn fold_mac(&mut self, mac: syntax::ast::Mac) -> syntax::ast::Mac {
+            fold::noop_fold_mac(mac, self)
+        }
+    }
+
+    StripAttributeFolder.fold_crate(krate)
+}
+
 #[cfg(not(feature = "with-syntex"))]
 pub fn register(reg: &mut rustc_plugin::Registry) {
     use syntax::symbol::Symbol;
-    use syntax::ext::base::MultiDecorator;
+    use syntax::ext::base::{MultiDecorator, MultiModifier};
     use syntax::feature_gate::AttributeType;
 
     reg.register_macro("mock", generate_mock);
+    reg.register_macro("mock_from_file", generate_mock_from_file);
     reg.register_syntax_extension(Symbol::intern("derive_Mock"),
                                   MultiDecorator(Box::new(derive_mock)));
+    reg.register_syntax_extension(Symbol::intern("mockable"),
+                                  MultiModifier(Box::new(mockable)));
     reg.register_attribute("derive_Mock".to_owned(), AttributeType::Whitelisted);
+    reg.register_attribute("mock".to_owned(), AttributeType::Whitelisted);
 }
 
 #[cfg(feature = "with-syntex")]
@@ -49,6 +90,44 @@ pub fn expand_str(src: &str) -> Result<String, syntex::Error> {
     syntex::with_extra_stack(expand_thread)
 }
 
+/// Same as `expand_str`, but fails when expansion reports errors.
+/// `Registry::expand_str` doesn't check for them, unlike `Registry::expand`,
+/// so source is expanded through temporary files.
+#[cfg(feature = "with-syntex")]
+fn expand_str_checked(src: &str) -> Result<String, syntex::Error> {
+    use std::io::{Read, Write};
+
+    let (src_path, mut src_file) = try!(create_temp_file());
+    let (dst_path, _) = try!(create_temp_file());
+    let result = src_file.write_all(src.as_bytes()).map_err(syntex::Error::from)
+        .and_then(|()| expand(&src_path, &dst_path))
+        .and_then(|()| {
+            let mut code = String::new();
+            try!(std::fs::File::open(&dst_path).and_then(|mut file| file.read_to_string(&mut code)));
+            Ok(code)
+        });
+    let _ = std::fs::remove_file(&src_path);
+    let _ = std::fs::remove_file(&dst_path);
+    result
+}
+
+/// Creates new file with unique name in temporary directory.
+#[cfg(feature = "with-syntex")]
+fn create_temp_file() -> std::io::Result<(std::path::PathBuf, std::fs::File)> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or(0);
+    let mut index = 0;
+    loop {
+        let path = std::env::temp_dir().join(format!("mockers-{}-{}.rs", nanos, index));
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(ref err) if err.kind() == std::io::ErrorKind::AlreadyExists => index += 1,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
 #[cfg(feature = "with-syntex")]
 pub fn expand<S, D>(src: S, dst: D) -> Result<(), syntex::Error>
     where S: AsRef<std::path::Path>,
@@ -63,3 +142,161 @@ pub fn expand<S, D>(src: S, dst: D) -> Result<(), syntex::Error>
 
     syntex::with_extra_stack(expand_thread)
 }
+
+/// Generate mock for traits defined in `src` file and write it to `dst`,
+/// like `mock_from_file!` does. `trait_mod_path` is path to module
+/// containing traits as seen from code where mock will be included.
+///
+/// `cargo:rerun-if-changed` line is printed for `src`, so it's intended
+/// to be called from build script.
+#[cfg(feature = "with-syntex")]
+pub fn mock_from_file<S, D>(mock_name: &str, trait_mod_path: &str, src: S,
+                            trait_names: &[&str], dst: D) -> Result<(), syntex::Error>
+    where S: AsRef<std::path::Path>,
+          D: AsRef<std::path::Path>,
+{
+    use std::io::Write;
+
+    println!("cargo:rerun-if-changed={}", src.as_ref().display());
+    let code = try!(mock_from_file_str(mock_name, trait_mod_path, src, trait_names));
+    let mut file = try!(std::fs::File::create(dst));
+    try!(file.write_all(code.as_bytes()));
+    Ok(())
+}
+
+/// Same as `mock_from_file`, but returns generated code and prints nothing.
+#[cfg(feature = "with-syntex")]
+pub fn mock_from_file_str<S>(mock_name: &str, trait_mod_path: &str, src: S,
+                             trait_names: &[&str]) -> Result<String, syntex::Error>
+    where S: AsRef<std::path::Path>,
+{
+    // Macro is expanded in temporary file and resolves path relative to it.
+    let src = try!(std::env::current_dir()).join(src);
+    let macro_src = format!("mock_from_file!({}, {}, {:?}, {});\n",
+                            mock_name, trait_mod_path,
+                            src.to_string_lossy(), trait_names.join(", "));
+    expand_str_checked(&macro_src)
+}
+
+/// Generate mocks for all traits marked with `#[derive(Mock)]` or
+/// `#[mockable]` in crate which root (`lib.rs` or `main.rs`) is in
+/// `src_dir` and write them to `dst`.
+///
+/// Generated code mirrors crate module structure, so mock for
+/// `::air::AirConditioner` is `air::AirConditionerMock` relative to
+/// module where `dst` is included. Each module glob-imports public items
+/// of original one, so types used by trait methods are resolved. Items
+/// of crate root are imported with `use super::*`, so `dst` must be
+/// included into module which is direct child of crate root.
+///
+/// `cargo:rerun-if-changed` line is printed for `src_dir` itself, so
+/// adding new source file reruns build script, and for each source file
+/// in it. So it's intended to be called from build script.
+///
+/// Note that crate still must be compiled with marker attributes on
+/// traits, and stable compiler rejects both `#[mockable]` and
+/// `#[derive(Mock)]` on trait. So this approach needs nightly Rust
+/// with `#![feature(custom_attribute)]`, although mocks are generated
+/// with `syntex`.
+#[cfg(feature = "with-syntex")]
+pub fn generate_mocks<S, D>(src_dir: S, dst: D) -> Result<(), syntex::Error>
+    where S: AsRef<std::path::Path>,
+          D: AsRef<std::path::Path>,
+{
+    use std::io::Write;
+    use syntax::parse::{self, ParseSess};
+
+    let src_dir = src_dir.as_ref();
+    let mut sources = Vec::new();
+    try!(find_sources(src_dir, &mut sources));
+    sources.sort();
+    println!("cargo:rerun-if-changed={}", src_dir.display());
+    for source in &sources {
+        println!("cargo:rerun-if-changed={}", source.display());
+    }
+
+    let root = ["lib.rs", "main.rs"].iter().map(|name| src_dir.join(name)).find(|path| path.is_file());
+    let root = match root {
+        Some(root) => root,
+        None => return Err(syntex::Error::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("neither `lib.rs` nor `main.rs` found in `{}`", src_dir.display())))),
+    };
+    let sess = ParseSess::new();
+    let krate = match parse::parse_crate_from_file(&root, &sess) {
+        Ok(krate) => krate,
+        Err(mut err) => {
+            err.emit();
+            return Err(syntex::Error::Parse);
+        }
+    };
+
+    // Mocks for traits defined in crate root are placed directly into
+    // module including `dst`.
+    let mut mock_src = "#[allow(unused_imports)]\nuse super::*;\n".to_owned();
+    collect_mocks(&krate.module.items, &mut vec![], &mut mock_src);
+    let code = try!(expand_str_checked(&mock_src));
+    let mut file = try!(std::fs::File::create(dst));
+    try!(file.write_all(code.as_bytes()));
+    Ok(())
+}
+
+/// Recursively find all Rust source files in directory.
+#[cfg(feature = "with-syntex")]
+fn find_sources(dir: &std::path::Path, sources: &mut Vec<std::path::PathBuf>) -> std::io::Result<()> {
+    for entry in try!(std::fs::read_dir(dir)) {
+        let path = try!(entry).path();
+        if path.is_dir() {
+            try!(find_sources(&path, sources));
+        } else if path.extension().map_or(false, |ext| ext == "rs") {
+            sources.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Write `mock!` invocation for each marked trait in module items,
+/// submodules are written as nested `pub mod`.
+#[cfg(feature = "with-syntex")]
+fn collect_mocks(items: &[syntax::ptr::P<syntax::ast::Item>], mod_path: &mut Vec<String>, out: &mut String) {
+    use syntax::ast::ItemKind;
+    use syntax::print::pprust;
+
+    for item in items {
+        match item.node {
+            // Marker isn't checked in pattern guard, `any` borrows iterator mutably.
+            ItemKind::Trait(..) => if item.attrs.iter().any(is_mock_marker) {
+                let mut item = (**item).clone();
+                item.attrs.retain(|attr| !is_mock_marker(attr));
+                // Crate root is given as `self`, it gives global path
+                // in syntex configuration.
+                let trait_mod_path = if mod_path.is_empty() {
+                    "self".to_owned()
+                } else {
+                    format!("::{}", mod_path.join("::"))
+                };
+                out.push_str(&format!("mock!{{\n{}Mock,\n{},\n{}\n}}\n",
+                                      item.ident, trait_mod_path, pprust::item_to_string(&item)));
+            },
+            ItemKind::Mod(ref module) => {
+                mod_path.push(item.ident.to_string());
+                let mut mod_src = String::new();
+                collect_mocks(&module.items, mod_path, &mut mod_src);
+                if !mod_src.is_empty() {
+                    out.push_str(&format!("pub mod {} {{\n#[allow(unused_imports)]\nuse ::{}::*;\n{}}}\n",
+                                          item.ident, mod_path.join("::"), mod_src));
+                }
+                mod_path.pop();
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Whether attribute is `#[derive(Mock)]` or `#[mockable]`.
+#[cfg(feature = "with-syntex")]
+fn is_mock_marker(attr: &syntax::ast::Attribute) -> bool {
+    attr.check_name("mockable") ||
+        (attr.check_name("derive") &&
+         attr.meta_item_list().map_or(false, |items| items.iter().any(|item| item.check_name("Mock"))))
+}
@@ -0,0 +1,140 @@
+#![cfg(feature = "with-syntex")]
+
+///! Test command line handling of `mockers-gen` tool.
+
+extern crate mockers_codegen;
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use mockers_codegen::cli::{self, Options, EXIT_FAILURE, EXIT_USAGE};
+
+const SOURCE: &'static str = "tests/support/thermostat.rs";
+
+fn args(args: &[&str]) -> std::vec::IntoIter<String> {
+    args.iter().map(|&arg| arg.to_owned()).collect::<Vec<_>>().into_iter()
+}
+
+/// Path to file in output directory, each test uses its own file.
+fn output_path(name: &str) -> String {
+    Path::new(env!("OUT_DIR")).join(name).to_string_lossy().into_owned()
+}
+
+fn read_file(path: &str) -> String {
+    let mut content = String::new();
+    File::open(path).unwrap().read_to_string(&mut content).unwrap();
+    content
+}
+
+fn write_file(path: &str, content: &str) {
+    File::create(path).unwrap().write_all(content.as_bytes()).unwrap();
+}
+
+/// Runs tool and returns exit status with `stderr` output.
+fn run(arguments: &[&str]) -> (i32, String) {
+    let mut stderr = Vec::new();
+    let status = cli::main(args(arguments), &mut stderr);
+    (status, String::from_utf8(stderr).unwrap())
+}
+
+#[test]
+fn test_parse_args() {
+    let options = cli::parse_args(args(&["--output", "mocks.rs", "src/air.rs", "Sensor", "Thermostat+Sensor"]));
+    assert_eq!(options, Ok(Options {
+        check: false,
+        module: "self".to_owned(),
+        output: "mocks.rs".to_owned(),
+        source: "src/air.rs".to_owned(),
+        traits: vec!["Sensor".to_owned(), "Thermostat+Sensor".to_owned()],
+    }));
+}
+
+#[test]
+fn test_parse_args_options() {
+    let options = cli::parse_args(args(&["--check", "src/air.rs", "--module", "::air", "-o", "mocks.rs", "Sensor"]))
+        .unwrap();
+    assert!(options.check);
+    assert_eq!(options.module, "::air");
+    assert_eq!(options.output, "mocks.rs");
+    assert_eq!(options.source, "src/air.rs");
+    assert_eq!(options.traits, vec!["Sensor".to_owned()]);
+}
+
+#[test]
+fn test_parse_args_errors() {
+    assert_eq!(cli::parse_args(args(&["src/air.rs", "Sensor"])),
+               Err("output file must be given with `--output`".to_owned()));
+    assert_eq!(cli::parse_args(args(&["-o", "mocks.rs", "src/air.rs"])),
+               Err("source file and at least one trait name are expected".to_owned()));
+    assert_eq!(cli::parse_args(args(&["-o", "mocks.rs", "--module"])),
+               Err("module path expected after `--module`".to_owned()));
+    assert_eq!(cli::parse_args(args(&["src/air.rs", "Sensor", "--output"])),
+               Err("file expected after `--output`".to_owned()));
+    assert_eq!(cli::parse_args(args(&["--force", "-o", "mocks.rs", "src/air.rs", "Sensor"])),
+               Err("unknown option `--force`".to_owned()));
+}
+
+#[test]
+fn test_usage_error() {
+    let (status, stderr) = run(&["src/air.rs", "Sensor"]);
+    assert_eq!(status, EXIT_USAGE);
+    assert!(stderr.contains(cli::USAGE), "{}", stderr);
+}
+
+#[test]
+fn test_output() {
+    let output = output_path("test_output.rs");
+    let (status, stderr) = run(&["--module", "::thermostat", "--output", &output,
+                                 SOURCE, "Sensor", "Thermostat+Sensor"]);
+    assert_eq!(status, 0, "{}", stderr);
+    let code = read_file(&output);
+    assert!(code.starts_with("// Generated by mockers-gen from `tests/support/thermostat.rs`"), "{}", code);
+    assert!(code.contains("SensorMock"), "{}", code);
+    assert!(code.contains("ThermostatMock"), "{}", code);
+}
+
+#[test]
+fn test_check_output() {
+    assert_eq!(cli::check_output("mocks.rs", "code", "code"), Ok(()));
+    assert_eq!(cli::check_output("mocks.rs", "old code", "code"),
+               Err("`mocks.rs` is stale, rerun mockers-gen without `--check`".to_owned()));
+}
+
+#[test]
+fn test_check_fresh() {
+    let output = output_path("test_check_fresh.rs");
+    assert_eq!(run(&["-o", &output, SOURCE, "Sensor"]).0, 0);
+    let (status, stderr) = run(&["--check", "-o", &output, SOURCE, "Sensor"]);
+    assert_eq!(status, 0, "{}", stderr);
+}
+
+#[test]
+fn test_check_stale() {
+    let output = output_path("test_check_stale.rs");
+    assert_eq!(run(&["-o", &output, SOURCE, "Sensor"]).0, 0);
+    let stale = read_file(&output).replace("temperature", "humidity");
+    write_file(&output, &stale);
+
+    let (status, stderr) = run(&["--check", "-o", &output, SOURCE, "Sensor"]);
+    assert_eq!(status, EXIT_FAILURE);
+    assert!(stderr.contains("is stale, rerun mockers-gen without `--check`"), "{}", stderr);
+    // File isn't overwritten by check.
+    assert_eq!(read_file(&output), stale);
+}
+
+#[test]
+fn test_check_missing_output() {
+    let output = output_path("test_check_missing_output.rs");
+    let (status, stderr) = run(&["--check", "-o", &output, SOURCE, "Sensor"]);
+    assert_eq!(status, EXIT_FAILURE);
+    assert!(stderr.contains(&format!("can't read `{}`", output)), "{}", stderr);
+}
+
+#[test]
+fn test_unknown_trait() {
+    let output = output_path("test_unknown_trait.rs");
+    let (status, stderr) = run(&["-o", &output, SOURCE, "Thermometer"]);
+    assert_eq!(status, EXIT_FAILURE);
+    assert!(stderr.contains("can't generate mock for `Thermometer`"), "{}", stderr);
+}
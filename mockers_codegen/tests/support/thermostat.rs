@@ -0,0 +1,11 @@
+//! Traits used by `mockers_gen` test, mocks are generated for them
+//! from this file.
+
+pub trait Sensor {
+    fn temperature(&self) -> i32;
+}
+
+pub trait Thermostat: Sensor {
+    fn set_target(&mut self, target: i32);
+    fn target(&self) -> i32;
+}
@@ -3,7 +3,7 @@ extern crate mockers_codegen;
 
 use proc_macro::TokenStream;
 
-#[proc_macro_derive(Mock)]
+#[proc_macro_derive(Mock, attributes(mock))]
 pub fn derive_mock(input: TokenStream) -> TokenStream {
     let item = format!("#[derive(Mock)]\n{}", input);
     let expanded = mockers_codegen::expand_str(&item).unwrap();
@@ -4,24 +4,44 @@
 
 set -e
 
+# Compiler plugin uses unstable compiler internals, so nightly is pinned
+# to known good one, and stable is pinned to one of the same time.
+NIGHTLY=nightly-2017-01-25
+STABLE=1.15.1
+
 # `mockers` library itself can be build with any Rust channel
 # if you turn off default "nightly" feature.
-(echo Build mockers/nightly/no-default-features ; cd mockers && cargo +nightly build --no-default-features)
-(echo Build mockers/stable/no-default-features ; cd mockers && cargo +stable  build --no-default-features)
+(echo Build mockers/nightly/no-default-features ; cd mockers && cargo +$NIGHTLY build --no-default-features)
+(echo Build mockers/stable/no-default-features ; cd mockers && cargo +$STABLE  build --no-default-features)
 # Full-features `mockers` can be build by nightly Rust only.
-(echo mockers/nighly ; cd mockers && cargo +nightly build)
+(echo mockers/nighly ; cd mockers && cargo +$NIGHTLY build)
 # Tests use `mockers_macros` and thus are only runnable with nightly Rust.
-(echo mockers/nightly/test ; cd mockers && cargo +nightly test)
+(echo mockers/nightly/test ; cd mockers && cargo +$NIGHTLY test)
+(echo mockers/nightly/test/std_mocks ; cd mockers && cargo +$NIGHTLY test --features std_mocks)
 # `mockers_macros` and `mockers_derive` use nightly Rust features.
-(echo mockers_macros/nightly ; cd mockers_macros && cargo +nightly build)
-(echo mockers_derive/nightly ; cd mockers_derive && cargo +nightly build)
+(echo mockers_macros/nightly ; cd mockers_macros && cargo +$NIGHTLY build)
+(echo mockers_derive/nightly ; cd mockers_derive && cargo +$NIGHTLY build)
 # `mockers_codegen` can be build with nightly and with stable Rust
 # (using `syntex`).
-(echo mockers_codegen/nightly ; cd mockers_codegen && cargo +nightly build)
-(echo mockers_codegen/nightly/with-syntex ; cd mockers_codegen && cargo +nightly build --features=with-syntex)
-(echo mockers_codegen/stable/with-syntex; cd mockers_codegen && cargo +stable  build --features=with-syntex)
+(echo mockers_codegen/nightly ; cd mockers_codegen && cargo +$NIGHTLY build)
+(echo mockers_codegen/nightly/with-syntex ; cd mockers_codegen && cargo +$NIGHTLY build --features=with-syntex)
+(echo mockers_codegen/stable/with-syntex; cd mockers_codegen && cargo +$STABLE  build --features=with-syntex)
+# Doc comments of codegen contain examples of generated code, which
+# aren't doctests, so only `mockers-gen` tool tests are run.
+(echo mockers_codegen/stable/with-syntex/test; cd mockers_codegen && cargo +$STABLE  test --features=with-syntex --test mockers_gen)
 # Examples
-(echo air_macro/nightly/test ; cd examples/air_macro   && cargo +nightly test)
-(echo air_codegen/nightly/test ; cd examples/air_codegen && cargo +nightly test)
-(echo air_codegen/stable/test ; cd examples/air_codegen && cargo +stable  test)
+(echo air_macro/nightly/test ; cd examples/air_macro   && cargo +$NIGHTLY test)
+(echo air_codegen/nightly/test ; cd examples/air_codegen && cargo +$NIGHTLY test)
+(echo air_codegen/stable/test ; cd examples/air_codegen && cargo +$STABLE  test)
+(echo air_generated/nightly/test ; cd examples/air_generated && cargo +$NIGHTLY test)
 
+# Lints. Clippy uses compiler internals too, so its version matches pinned
+# nightly: `cargo +$NIGHTLY install clippy --vers 0.0.111`.
+(echo mockers/nightly/clippy ; cd mockers && cargo +$NIGHTLY clippy --features std_mocks -- -D warnings)
+(echo mockers_macros/nightly/clippy ; cd mockers_macros && cargo +$NIGHTLY clippy -- -D warnings)
+(echo mockers_derive/nightly/clippy ; cd mockers_derive && cargo +$NIGHTLY clippy -- -D warnings)
+(echo mockers_codegen/nightly/clippy ; cd mockers_codegen && cargo +$NIGHTLY clippy -- -D warnings)
+(echo mockers_codegen/nightly/with-syntex/clippy ; cd mockers_codegen && cargo +$NIGHTLY clippy --features=with-syntex -- -D warnings)
+(echo air_macro/nightly/clippy ; cd examples/air_macro   && cargo +$NIGHTLY clippy -- -D warnings)
+(echo air_codegen/nightly/clippy ; cd examples/air_codegen && cargo +$NIGHTLY clippy -- -D warnings)
+(echo air_generated/nightly/clippy ; cd examples/air_generated && cargo +$NIGHTLY clippy -- -D warnings)